@@ -0,0 +1,258 @@
+use std::{fs, io::{self, Write}, path::{Path, PathBuf}, process::ExitCode, time::Duration};
+
+use chrono::{DateTime, Local};
+use clap::{Parser, Subcommand, ValueEnum};
+use common::{protocol::Capability, response::{export::ExportTable, now_playing::NowPlayingResponse}};
+use lan_core::{api::{self, ApiError}, processes, telemetry};
+use log::{info, warn};
+use serde::Serialize;
+use tokio::time::{interval, MissedTickBehavior};
+
+/// reports running games to a LAN Manager server without the GUI,
+/// the config is read from `config.toml` or `LAN_MANAGER_CONFIG`
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    /// print JSON instead of text
+    #[arg(long, global = true)]
+    json: bool,
+    #[command(subcommand)]
+    command: Command
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// detect running games and report them once
+    Report,
+    /// show what the party is playing
+    Status,
+    /// list the games catalogue, including custom games
+    Games,
+    /// keep reporting until interrupted, then leave the party
+    Watch {
+        /// seconds between reports
+        #[arg(long, default_value_t = 5)]
+        interval: u64
+    },
+    /// export sessions and per-player and per-game totals, requires the password
+    Export {
+        /// RFC 3339 timestamp, e.g. 2025-10-03T18:00:00+02:00
+        #[arg(long)]
+        from: Option<DateTime<Local>>,
+        /// RFC 3339 timestamp
+        #[arg(long)]
+        to: Option<DateTime<Local>>,
+        #[arg(long, value_enum, default_value_t = Format::Json)]
+        format: Format,
+        /// the csv table to print
+        #[arg(long, value_enum, default_value_t = Table::Sessions)]
+        table: Table,
+        /// write every table into this directory instead of printing
+        #[arg(long)]
+        output: Option<PathBuf>
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+enum Format {
+    Json,
+    Csv
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+enum Table {
+    Sessions,
+    Players,
+    Games
+}
+
+impl From<Table> for ExportTable {
+    fn from(table: Table) -> ExportTable {
+        match table {
+            Table::Sessions => ExportTable::Sessions,
+            Table::Players => ExportTable::Players,
+            Table::Games => ExportTable::Games
+        }
+    }
+}
+
+/// ignores write errors, output piped into e.g. `head` may be closed early
+fn print<T: Serialize>(json: bool, value: &T, text: impl FnOnce(&T) -> String) {
+    let output = if json {
+        serde_json::to_string_pretty(value).expect("could not serialize output")
+    } else {
+        text(value)
+    };
+
+    let _ = writeln!(io::stdout(), "{output}");
+}
+
+fn format_status(response: &NowPlayingResponse) -> String {
+    let mut lines = vec![format!("{} player(s) online", response.online)];
+
+    for entry in &response.active {
+        let players = entry.players.iter()
+            .map(|p| p.name.as_str())
+            .collect::<Vec<&str>>()
+            .join(", ");
+
+        lines.push(format!("{}: {players}", entry.game.label));
+    }
+
+    if !response.private.is_empty() {
+        lines.push(format!("{} player(s) private", response.private.len()));
+    }
+
+    if let Some(majority) = processes::majority(response) {
+        lines.push(format!("majority is playing {}", majority.game.label));
+    }
+
+    lines.join("\n")
+}
+
+async fn report(json: bool) -> Result<(), ApiError> {
+    let report = processes::report().await?;
+    let mut reported = report.reported.unwrap_or_default().into_iter().collect::<Vec<String>>();
+
+    reported.sort();
+    print(json, &reported, |reported| if reported.is_empty() {
+        "reported nothing".to_string()
+    } else {
+        format!("reported {}", reported.join(", "))
+    });
+
+    Ok(())
+}
+
+fn write_file(path: &Path, contents: &str) -> Result<(), ApiError> {
+    fs::write(path, contents)
+        .map_err(|e| ApiError::internal(format!("could not write {}: {e}", path.display())))
+}
+
+async fn export(from: Option<DateTime<Local>>, to: Option<DateTime<Local>>, format: Format, table: Table, output: Option<PathBuf>) -> Result<(), ApiError> {
+    let Some(output) = output else {
+        let contents = match format {
+            Format::Json => serde_json::to_string_pretty(&api::get_export(from, to).await?).expect("could not serialize export"),
+            Format::Csv => api::get_export_csv(table.into(), from, to).await?
+        };
+
+        let _ = write!(io::stdout(), "{contents}");
+
+        return Ok(());
+    };
+
+    fs::create_dir_all(&output)
+        .map_err(|e| ApiError::internal(format!("could not create {}: {e}", output.display())))?;
+
+    match format {
+        Format::Json => {
+            let export = api::get_export(from, to).await?;
+
+            write_file(&output.join("export.json"), &serde_json::to_string_pretty(&export).expect("could not serialize export"))?;
+        },
+        Format::Csv => for table in ExportTable::ALL {
+            write_file(&output.join(format!("{}.csv", table.name())), &api::get_export_csv(table, from, to).await?)?;
+        }
+    }
+
+    info!("exported to {}", output.display());
+
+    Ok(())
+}
+
+async fn watch(period: u64) -> Result<(), ApiError> {
+    let mut reports = interval(Duration::from_secs(period.max(1)));
+    let mut refreshes = interval(Duration::from_secs(60));
+    let mut telemetry = interval(Duration::from_secs(5 * 60));
+
+    for timer in [&mut reports, &mut refreshes, &mut telemetry] {
+        timer.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    }
+
+    // created once, so signals arriving while a tick is handled are not lost
+    let shutdown = shutdown_signal()?;
+
+    tokio::pin!(shutdown);
+
+    loop {
+        tokio::select! {
+            // a pending stop wins over ticks that are due
+            biased;
+
+            _ = &mut shutdown => break,
+            _ = reports.tick() => if let Err(e) = processes::report().await {
+                warn!("failed to report: {e}");
+            },
+            _ = refreshes.tick() => if let Err(e) = api::refresh_games().await {
+                warn!("failed to refresh games: {e}");
+            },
+            _ = telemetry.tick() => if let Err(e) = telemetry::poll().await {
+                warn!("failed to report unknown processes: {e}");
+            }
+        }
+    }
+
+    // tell the server we are gone instead of waiting for the heartbeat to expire
+    if api::get_info().await?.supports(Capability::Leave) {
+        api::delete_now_playing().await?;
+        info!("left the party");
+    }
+
+    Ok(())
+}
+
+/// listens for SIGTERM and Ctrl+C right away, the future completes once one of them arrived
+#[cfg(unix)]
+fn shutdown_signal() -> Result<impl Future<Output = ()>, ApiError> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let listen = |kind: SignalKind, name: &str| signal(kind)
+        .map_err(|e| ApiError::internal(format!("could not listen for {name}: {e}")));
+    let mut sigterm = listen(SignalKind::terminate(), "SIGTERM")?;
+    let mut sigint = listen(SignalKind::interrupt(), "Ctrl+C")?;
+
+    Ok(async move {
+        tokio::select! {
+            _ = sigterm.recv() => {},
+            _ = sigint.recv() => {}
+        }
+    })
+}
+
+#[cfg(not(unix))]
+fn shutdown_signal() -> Result<impl Future<Output = ()>, ApiError> {
+    let mut ctrl_c = tokio::signal::windows::ctrl_c()
+        .map_err(|e| ApiError::internal(format!("could not listen for Ctrl+C: {e}")))?;
+
+    Ok(async move {
+        ctrl_c.recv().await;
+    })
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
+
+    let cli = Cli::parse();
+    let res = match cli.command {
+        Command::Report => report(cli.json).await,
+        Command::Status => api::get_now_playing().await
+            .map(|response| print(cli.json, &response, format_status)),
+        Command::Games => api::get_catalogue().await
+            .map(|games| print(cli.json, &games, |games| games.iter()
+                .map(|g| format!("{}\t{}", g.name, g.label))
+                .collect::<Vec<String>>()
+                .join("\n"))),
+        Command::Watch { interval } => watch(interval).await,
+        Command::Export { from, to, format, table, output } => export(from, to, format, table, output).await
+    };
+
+    match res {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+
+            ExitCode::FAILURE
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,650 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+use common::{game::Game, protocol::{CLIENT_VERSION_HEADER, PROTOCOL_VERSION, PROTOCOL_VERSION_HEADER}, response::{coplay::{BuddiesResponse, CoPlayGraph}, error::ErrorCode, export::{ExportResponse, ExportTable}, games::GamesResponse, history::HistoryResponse, info::InfoResponse, leaderboards::{AchievementsResponse, LeaderboardsResponse}, now_playing::{NowPlayingEntry, NowPlayingResponse}, players::PlayersResponse, recap::RecapFormat, suggestions::{GameApproval, SuggestedGame, SuggestionsResponse}, timeline::TimelineResponse, unknown_processes::{UnknownProcessesReport, UnknownProcessesResponse}}};
+use log::warn;
+use reqwest::{header::{self, HeaderMap, HeaderValue}, Client, RequestBuilder, Response, StatusCode, Url};
+use serde::de::DeserializeOwned;
+
+use crate::error::ClientError;
+
+/// outcome of a request sent with an `ETag`
+pub enum Conditional<T> {
+    Modified { value: T, etag: Option<String> },
+    NotModified
+}
+
+pub struct LanClientBuilder {
+    base_url: String,
+    token: Option<String>,
+    admin_token: Option<String>,
+    client_version: String,
+    timeout: Duration,
+    retries: u32,
+    retry_delay: Duration
+}
+
+impl LanClientBuilder {
+    /// the hashed password players report with
+    pub fn token(mut self, token: impl Into<String>) -> LanClientBuilder {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// the hashed `ADMIN_PASSWORD`, needed for suggestions and unknown processes
+    pub fn admin_token(mut self, token: impl Into<String>) -> LanClientBuilder {
+        self.admin_token = Some(token.into());
+        self
+    }
+
+    /// sent as `X-Client-Version` and shown to other players
+    pub fn client_version(mut self, version: impl Into<String>) -> LanClientBuilder {
+        self.client_version = version.into();
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> LanClientBuilder {
+        self.timeout = timeout;
+        self
+    }
+
+    /// additional attempts for idempotent requests failing with connection or server errors
+    pub fn retries(mut self, retries: u32, delay: Duration) -> LanClientBuilder {
+        self.retries = retries;
+        self.retry_delay = delay;
+        self
+    }
+
+    pub fn build(self) -> Result<LanClient, ClientError> {
+        let mut headers = HeaderMap::new();
+        let client_version = HeaderValue::from_str(&self.client_version)
+            .map_err(|e| ClientError::config(format!("invalid client version: {e}")))?;
+        let base_url = Url::parse(&self.base_url)
+            .map_err(|e| ClientError::config(format!("invalid base url: {e}")))?;
+
+        if base_url.cannot_be_a_base() {
+            return Err(ClientError::config(format!("invalid base url: {base_url}")));
+        }
+
+        headers.insert(CLIENT_VERSION_HEADER, client_version);
+        headers.insert(PROTOCOL_VERSION_HEADER, HeaderValue::from(PROTOCOL_VERSION));
+
+        let http = Client::builder()
+            .timeout(self.timeout)
+            .default_headers(headers)
+            .build()
+            .map_err(|e| ClientError::config(e.to_string()))?;
+
+        Ok(LanClient {
+            http,
+            base_url,
+            token: self.token,
+            admin_token: self.admin_token,
+            retries: self.retries,
+            retry_delay: self.retry_delay
+        })
+    }
+}
+
+/// one method per `/api/v1` endpoint, cheap to clone
+#[derive(Clone)]
+pub struct LanClient {
+    http: Client,
+    base_url: Url,
+    token: Option<String>,
+    admin_token: Option<String>,
+    retries: u32,
+    retry_delay: Duration
+}
+
+impl LanClient {
+    pub fn builder(base_url: impl Into<String>) -> LanClientBuilder {
+        LanClientBuilder {
+            base_url: base_url.into(),
+            token: None,
+            admin_token: None,
+            client_version: env!("CARGO_PKG_VERSION").to_string(),
+            timeout: Duration::from_secs(5),
+            retries: 0,
+            retry_delay: Duration::from_secs(1)
+        }
+    }
+
+    pub fn base_url(&self) -> &str {
+        self.base_url.as_str()
+    }
+
+    /// `segments` below `/api/v1`, each one percent-encoded
+    fn url<'a>(&self, segments: impl IntoIterator<Item = &'a str>) -> Url {
+        let mut url = self.base_url.clone();
+
+        url.path_segments_mut()
+            .expect("base url checked in build")
+            .pop_if_empty()
+            .extend(["api", "v1"])
+            .extend(segments);
+
+        url
+    }
+
+    fn authorized(&self, req: RequestBuilder) -> RequestBuilder {
+        match &self.token {
+            Some(token) => req.bearer_auth(token),
+            None => req
+        }
+    }
+
+    fn admin(&self, req: RequestBuilder) -> RequestBuilder {
+        match &self.admin_token {
+            Some(token) => req.bearer_auth(token),
+            None => req
+        }
+    }
+
+    /// sends `req`, retrying transient failures if `idempotent`
+    async fn send(&self, req: RequestBuilder, idempotent: bool) -> Result<Response, ClientError> {
+        let retries = if idempotent { self.retries } else { 0 };
+        let mut attempt = 0;
+
+        loop {
+            let Some(current) = req.try_clone() else {
+                return req.send().await.map_err(ClientError::connection);
+            };
+            let res = match current.send().await {
+                Ok(res) if res.status().is_server_error() => Err(ClientError::from_response(res).await),
+                Ok(res) => Ok(res),
+                Err(e) => Err(ClientError::connection(e))
+            };
+
+            match res {
+                Err(e) if e.is_transient() && attempt < retries => {
+                    attempt += 1;
+                    warn!("request failed, retrying ({attempt}/{retries}): {e}");
+                    tokio::time::sleep(self.retry_delay).await;
+                },
+                res => return res
+            }
+        }
+    }
+
+    async fn json<T: DeserializeOwned>(&self, req: RequestBuilder, idempotent: bool) -> Result<T, ClientError> {
+        let res = self.send(req, idempotent).await?;
+
+        if !res.status().is_success() {
+            return Err(ClientError::from_response(res).await);
+        }
+
+        res.json::<T>().await.map_err(ClientError::invalid_response)
+    }
+
+    async fn empty(&self, req: RequestBuilder, idempotent: bool) -> Result<(), ClientError> {
+        let res = self.send(req, idempotent).await?;
+
+        if !res.status().is_success() {
+            return Err(ClientError::from_response(res).await);
+        }
+
+        Ok(())
+    }
+
+    async fn text(&self, req: RequestBuilder, idempotent: bool) -> Result<String, ClientError> {
+        let res = self.send(req, idempotent).await?;
+
+        if !res.status().is_success() {
+            return Err(ClientError::from_response(res).await);
+        }
+
+        res.text().await.map_err(ClientError::invalid_response)
+    }
+
+    fn with_range(req: RequestBuilder, from: Option<DateTime<Local>>, to: Option<DateTime<Local>>) -> RequestBuilder {
+        let mut req = req;
+
+        if let Some(from) = from {
+            req = req.query(&[("from", from.to_rfc3339())]);
+        }
+
+        if let Some(to) = to {
+            req = req.query(&[("to", to.to_rfc3339())]);
+        }
+
+        req
+    }
+
+    /// servers predating `/info` are treated as protocol 1 without capabilities
+    pub async fn info(&self) -> Result<InfoResponse, ClientError> {
+        let info = match self.json::<InfoResponse>(self.http.get(self.url(["info"])), true).await {
+            Err(ClientError::Server { code: ErrorCode::NotFound, .. }) => InfoResponse {
+                version: "unknown".to_string(),
+                protocol: 1,
+                min_protocol: 1,
+                capabilities: vec![]
+            },
+            res => res?
+        };
+
+        if info.min_protocol > PROTOCOL_VERSION {
+            return Err(ClientError::Incompatible {
+                server_version: info.version,
+                min_protocol: info.min_protocol
+            });
+        }
+
+        Ok(info)
+    }
+
+    /// reports `entry`, `None` if nothing changed since `last_update`
+    pub async fn put_now_playing(&self, entry: &NowPlayingEntry, last_update: Option<DateTime<Local>>) -> Result<Option<NowPlayingResponse>, ClientError> {
+        let mut req = self.authorized(self.http.put(self.url(["now-playing"]))).json(entry);
+
+        if let Some(last_update) = last_update {
+            req = req.query(&[("last_update", last_update.to_rfc3339())]);
+        }
+
+        let res = self.send(req, true).await?;
+
+        match res.status() {
+            StatusCode::OK => res.json::<NowPlayingResponse>().await
+                .map(Some)
+                .map_err(ClientError::invalid_response),
+            StatusCode::NOT_MODIFIED => Ok(None),
+            _ => Err(ClientError::from_response(res).await)
+        }
+    }
+
+    pub async fn now_playing(&self) -> Result<NowPlayingResponse, ClientError> {
+        self.json(self.authorized(self.http.get(self.url(["now-playing"]))), true).await
+    }
+
+    /// removes `player` right away instead of waiting for the heartbeat to expire
+    pub async fn leave(&self, player: &str) -> Result<(), ClientError> {
+        let req = self.authorized(self.http.delete(self.url(["now-playing"])))
+            .query(&[("player", player)]);
+
+        self.empty(req, true).await
+    }
+
+    pub async fn games(&self) -> Result<GamesResponse, ClientError> {
+        self.json(self.http.get(self.url(["games"])), true).await
+    }
+
+    /// the catalogue, unless it still matches `etag`
+    pub async fn games_if_changed(&self, etag: Option<&str>) -> Result<Conditional<GamesResponse>, ClientError> {
+        self.conditional(self.http.get(self.url(["games"])), etag, |res| async {
+            res.json::<GamesResponse>().await.map_err(ClientError::invalid_response)
+        }).await
+    }
+
+    /// the icon of `game` as PNG with edges of `size` pixels, unless it still matches `etag`
+    pub async fn game_icon(&self, game: &Game, size: u32, etag: Option<&str>) -> Result<Conditional<Vec<u8>>, ClientError> {
+        let req = self.http.get(self.url(["games", &game.name, "icon"]))
+            .query(&[("size", size)]);
+
+        self.conditional(req, etag, |res| async {
+            res.bytes().await
+                .map(|b| b.to_vec())
+                .map_err(ClientError::invalid_response)
+        }).await
+    }
+
+    async fn conditional<T, F: Future<Output = Result<T, ClientError>>>(&self, mut req: RequestBuilder, etag: Option<&str>, read: impl FnOnce(Response) -> F) -> Result<Conditional<T>, ClientError> {
+        if let Some(etag) = etag {
+            req = req.header(header::IF_NONE_MATCH, etag);
+        }
+
+        let res = self.send(req, true).await?;
+
+        match res.status() {
+            StatusCode::NOT_MODIFIED => Ok(Conditional::NotModified),
+            status if status.is_success() => {
+                let etag = res.headers().get(header::ETAG)
+                    .and_then(|e| e.to_str().ok())
+                    .map(str::to_owned);
+
+                Ok(Conditional::Modified {
+                    value: read(res).await?,
+                    etag
+                })
+            },
+            _ => Err(ClientError::from_response(res).await)
+        }
+    }
+
+    pub async fn history(&self, from: Option<DateTime<Local>>, to: Option<DateTime<Local>>) -> Result<HistoryResponse, ClientError> {
+        let req = Self::with_range(self.authorized(self.http.get(self.url(["history"]))), from, to);
+
+        self.json(req, true).await
+    }
+
+    /// games played per bucket of `step`, the server picks a step if `None`
+    pub async fn timeline(&self, from: Option<DateTime<Local>>, to: Option<DateTime<Local>>, step: Option<Duration>) -> Result<TimelineResponse, ClientError> {
+        let mut req = Self::with_range(self.authorized(self.http.get(self.url(["timeline"]))), from, to);
+
+        if let Some(step) = step {
+            req = req.query(&[("step", step.as_secs())]);
+        }
+
+        self.json(req, true).await
+    }
+
+    /// players connected by the time they spent in the same game
+    pub async fn coplay(&self, from: Option<DateTime<Local>>, to: Option<DateTime<Local>>) -> Result<CoPlayGraph, ClientError> {
+        self.json(Self::with_range(self.authorized(self.http.get(self.url(["coplay"]))), from, to), true).await
+    }
+
+    pub async fn buddies(&self, player: &str) -> Result<BuddiesResponse, ClientError> {
+        self.json(self.authorized(self.http.get(self.url(["buddies", player]))), true).await
+    }
+
+    /// sessions and totals overlapping `from` and `to`, requires the password even with public reads
+    pub async fn export(&self, from: Option<DateTime<Local>>, to: Option<DateTime<Local>>) -> Result<ExportResponse, ClientError> {
+        let req = Self::with_range(self.authorized(self.http.get(self.url(["export"]))), from, to);
+
+        self.json(req, true).await
+    }
+
+    /// one table of the export as csv with a header row
+    pub async fn export_csv(&self, table: ExportTable, from: Option<DateTime<Local>>, to: Option<DateTime<Local>>) -> Result<String, ClientError> {
+        let req = Self::with_range(self.authorized(self.http.get(self.url(["export"]))), from, to)
+            .query(&[("format", "csv"), ("table", table.name())]);
+
+        self.text(req, true).await
+    }
+
+    /// the party summary of `from` to `to` as a shareable document
+    pub async fn recap(&self, format: RecapFormat, from: Option<DateTime<Local>>, to: Option<DateTime<Local>>) -> Result<String, ClientError> {
+        let req = Self::with_range(self.authorized(self.http.get(self.url(["recap"]))), from, to)
+            .query(&[("format", format.name())]);
+
+        self.text(req, true).await
+    }
+
+    pub async fn leaderboards(&self) -> Result<LeaderboardsResponse, ClientError> {
+        self.json(self.authorized(self.http.get(self.url(["leaderboards"]))), true).await
+    }
+
+    pub async fn achievements(&self, player: &str) -> Result<AchievementsResponse, ClientError> {
+        self.json(self.authorized(self.http.get(self.url(["achievements", player]))), true).await
+    }
+
+    pub async fn players(&self) -> Result<PlayersResponse, ClientError> {
+        self.json(self.authorized(self.http.get(self.url(["players"]))), true).await
+    }
+
+    pub async fn suggest_game(&self, suggestion: &SuggestedGame) -> Result<(), ClientError> {
+        self.empty(self.authorized(self.http.post(self.url(["games", "suggestions"]))).json(suggestion), false).await
+    }
+
+    pub async fn suggestions(&self) -> Result<SuggestionsResponse, ClientError> {
+        self.json(self.admin(self.http.get(self.url(["games", "suggestions"]))), true).await
+    }
+
+    pub async fn approve_suggestion(&self, name: &str, approval: &GameApproval) -> Result<Game, ClientError> {
+        let req = self.admin(self.http.post(self.url(["games", "suggestions", name, "approve"]))).json(approval);
+
+        self.json(req, false).await
+    }
+
+    pub async fn reject_suggestion(&self, name: &str) -> Result<(), ClientError> {
+        self.empty(self.admin(self.http.delete(self.url(["games", "suggestions", name]))), true).await
+    }
+
+    pub async fn report_unknown_processes(&self, report: &UnknownProcessesReport) -> Result<(), ClientError> {
+        self.empty(self.authorized(self.http.post(self.url(["processes", "unknown"]))).json(report), false).await
+    }
+
+    pub async fn unknown_processes(&self) -> Result<UnknownProcessesResponse, ClientError> {
+        self.json(self.admin(self.http.get(self.url(["processes", "unknown"]))), true).await
+    }
+
+    pub async fn promote_process(&self, name: &str, approval: &GameApproval) -> Result<Game, ClientError> {
+        let req = self.admin(self.http.post(self.url(["processes", "unknown", name, "promote"]))).json(approval);
+
+        self.json(req, false).await
+    }
+
+    pub async fn dismiss_process(&self, name: &str) -> Result<(), ClientError> {
+        self.empty(self.admin(self.http.delete(self.url(["processes", "unknown", name]))), true).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use tokio::{io::{AsyncReadExt, AsyncWriteExt}, net::TcpListener};
+
+    use super::*;
+
+    fn response(status: &str, headers: &[(&str, &str)], body: &str) -> String {
+        let headers: String = headers.iter().map(|(name, value)| format!("{name}: {value}\r\n")).collect();
+
+        format!("HTTP/1.1 {status}\r\n{headers}content-length: {}\r\nconnection: close\r\n\r\n{body}", body.len())
+    }
+
+    /// answers one request after another with `responses`, returns the base url and the raw requests
+    async fn mock(responses: Vec<String>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(vec![]));
+        let received = requests.clone();
+
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = vec![];
+                let mut buf = [0; 4096];
+
+                // requests of these tests are small, the headers arrive before the body is needed
+                loop {
+                    let read = stream.read(&mut buf).await.unwrap();
+
+                    request.extend_from_slice(&buf[..read]);
+
+                    let text = String::from_utf8_lossy(&request);
+
+                    if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                        let length = head.lines()
+                            .find_map(|l| l.to_lowercase().strip_prefix("content-length:").map(|v| v.trim().parse::<usize>().unwrap()))
+                            .unwrap_or(0);
+
+                        if body.len() >= length {
+                            break;
+                        }
+                    }
+
+                    if read == 0 {
+                        break;
+                    }
+                }
+
+                received.lock().unwrap().push(String::from_utf8_lossy(&request).to_string());
+                stream.write_all(response.as_bytes()).await.unwrap();
+                let _ = stream.shutdown().await;
+            }
+        });
+
+        (url, requests)
+    }
+
+    fn connect(url: &str) -> LanClient {
+        LanClient::builder(url)
+            .token("secret")
+            .retries(2, Duration::from_millis(10))
+            .build()
+            .unwrap()
+    }
+
+    fn entry() -> NowPlayingEntry {
+        serde_json::from_value(serde_json::json!({ "player": { "id": "1", "name": "Alice" }, "games": [] })).unwrap()
+    }
+
+    #[tokio::test]
+    async fn maps_legacy_plain_text_errors_by_status() {
+        let (url, _) = mock(vec![
+            response("404 Not Found", &[], "no such player"),
+            response("429 Too Many Requests", &[], "slow down"),
+            response("418 I'm a teapot", &[], "")
+        ]).await;
+        let client = connect(&url);
+
+        for expected in [ErrorCode::NotFound, ErrorCode::TooManyRequests, ErrorCode::Unknown] {
+            match client.buddies("1").await {
+                Err(ClientError::Server { code, .. }) => assert_eq!(code, expected),
+                _ => panic!("expected a server error")
+            }
+        }
+
+        let (url, _) = mock(vec![response("404 Not Found", &[], "no such player")]).await;
+        let error = connect(&url).buddies("1").await.err().unwrap();
+
+        assert_eq!(error.to_string(), "404: no such player");
+    }
+
+    #[tokio::test]
+    async fn reads_structured_errors() {
+        let body = r#"{"code":"unprocessable_entity","message":"invalid name","details":"too long"}"#;
+        let (url, _) = mock(vec![response("422 Unprocessable Entity", &[("content-type", "application/json")], body)]).await;
+
+        match connect(&url).put_now_playing(&entry(), None).await {
+            Err(ClientError::Server { status, code, message, details }) => {
+                assert_eq!(status, 422);
+                assert_eq!(code, ErrorCode::UnprocessableEntity);
+                assert_eq!(message, "invalid name");
+                assert_eq!(details.as_deref(), Some("too long"));
+            },
+            _ => panic!("expected a server error")
+        }
+    }
+
+    #[tokio::test]
+    async fn reads_unknown_error_codes_of_newer_servers() {
+        let body = r#"{"code":"teapot","message":"short and stout","details":null}"#;
+        let (url, _) = mock(vec![response("400 Bad Request", &[], body)]).await;
+
+        assert!(matches!(connect(&url).players().await, Err(ClientError::Server { code: ErrorCode::Unknown, .. })));
+    }
+
+    #[tokio::test]
+    async fn info_falls_back_for_old_servers() {
+        let (url, requests) = mock(vec![response("404 Not Found", &[], "")]).await;
+        let info = connect(&url).info().await.unwrap();
+
+        assert_eq!(info.version, "unknown");
+        assert_eq!(info.protocol, 1);
+        assert!(info.capabilities.is_empty());
+        assert!(requests.lock().unwrap()[0].starts_with("GET /api/v1/info "));
+    }
+
+    #[tokio::test]
+    async fn info_rejects_newer_protocols() {
+        let body = format!(r#"{{"version":"9.0.0","protocol":{0},"min_protocol":{0},"capabilities":[]}}"#, PROTOCOL_VERSION + 1);
+        let (url, _) = mock(vec![response("200 OK", &[("content-type", "application/json")], &body)]).await;
+
+        match connect(&url).info().await {
+            Err(ClientError::Incompatible { server_version, min_protocol }) => {
+                assert_eq!(server_version, "9.0.0");
+                assert_eq!(min_protocol, PROTOCOL_VERSION + 1);
+            },
+            _ => panic!("expected an incompatible server")
+        }
+    }
+
+    #[tokio::test]
+    async fn conditional_requests_send_and_return_etags() {
+        let game = Game {
+            name: "cs2.exe".to_string(),
+            label: "Counter-Strike 2".to_string(),
+            icon: "cs2.png".to_string()
+        };
+        let (url, requests) = mock(vec![
+            response("200 OK", &[("etag", "\"abc\""), ("last-modified", "Mon, 19 Oct 2026 10:00:00 GMT")], "png"),
+            response("304 Not Modified", &[("etag", "\"abc\"")], ""),
+            response("200 OK", &[], "png")
+        ]).await;
+        let client = connect(&url);
+
+        match client.game_icon(&game, 64, None).await.unwrap() {
+            Conditional::Modified { value, etag } => {
+                assert_eq!(value, b"png");
+                assert_eq!(etag.as_deref(), Some("\"abc\""));
+            },
+            Conditional::NotModified => panic!("expected the icon")
+        }
+
+        assert!(matches!(client.game_icon(&game, 64, Some("\"abc\"")).await.unwrap(), Conditional::NotModified));
+        assert!(matches!(client.game_icon(&game, 64, None).await.unwrap(), Conditional::Modified { etag: None, .. }));
+
+        let requests = requests.lock().unwrap();
+
+        assert!(requests[0].starts_with("GET /api/v1/games/cs2.exe/icon?size=64 "));
+        assert!(!requests[0].to_lowercase().contains("if-none-match"));
+        assert!(requests[1].to_lowercase().contains("if-none-match: \"abc\""));
+    }
+
+    #[tokio::test]
+    async fn retries_idempotent_requests_on_server_errors() {
+        let (url, requests) = mock(vec![
+            response("503 Service Unavailable", &[], ""),
+            response("502 Bad Gateway", &[], ""),
+            response("200 OK", &[("content-type", "application/json")], r#"{"players":[]}"#)
+        ]).await;
+
+        assert!(connect(&url).players().await.unwrap().players.is_empty());
+        assert_eq!(requests.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_the_configured_retries() {
+        let (url, requests) = mock(vec![
+            response("500 Internal Server Error", &[], "boom"),
+            response("500 Internal Server Error", &[], "boom"),
+            response("500 Internal Server Error", &[], "boom")
+        ]).await;
+
+        assert!(matches!(connect(&url).players().await, Err(ClientError::Server { status: 500, code: ErrorCode::Internal, .. })));
+        assert_eq!(requests.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_client_errors_or_non_idempotent_requests() {
+        let (url, requests) = mock(vec![
+            response("401 Unauthorized", &[], ""),
+            response("503 Service Unavailable", &[], "")
+        ]).await;
+        let client = connect(&url);
+        let suggestion = SuggestedGame {
+            player: entry().player,
+            name: "b.exe".to_string(),
+            label: "B".to_string()
+        };
+
+        assert!(matches!(client.players().await, Err(ClientError::Server { code: ErrorCode::Unauthorized, .. })));
+        assert!(matches!(client.suggest_game(&suggestion).await, Err(ClientError::Server { status: 503, .. })));
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn retries_connection_errors() {
+        // nothing listens on the port once the listener is dropped
+        let url = {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+
+            format!("http://{}", listener.local_addr().unwrap())
+        };
+
+        let start = std::time::Instant::now();
+
+        assert!(matches!(connect(&url).players().await, Err(ClientError::Connection { .. })));
+        // two retries, each after the configured delay
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    }
+
+    #[tokio::test]
+    async fn recap_requests_the_format() {
+        let (url, requests) = mock(vec![response("200 OK", &[("content-type", "text/markdown")], "# LAN-Rückblick")]).await;
+
+        assert_eq!(connect(&url).recap(RecapFormat::Markdown, None, None).await.unwrap(), "# LAN-Rückblick");
+        assert!(requests.lock().unwrap()[0].starts_with("GET /api/v1/recap?format=markdown "));
+    }
+}
\ No newline at end of file
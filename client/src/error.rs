@@ -0,0 +1,95 @@
+use std::fmt::{self, Display};
+
+use common::response::error::{ErrorCode, ErrorResponse};
+use reqwest::{Response, StatusCode};
+use serde::Serialize;
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ClientError {
+    /// the client itself is misconfigured, e.g. an invalid base url
+    Config { message: String },
+    Connection { message: String },
+    InvalidResponse { message: String },
+    /// the server requires a newer protocol than this build speaks
+    Incompatible { server_version: String, min_protocol: u32 },
+    Server { status: u16, code: ErrorCode, message: String, details: Option<String> }
+}
+
+impl ClientError {
+    pub(crate) fn config(message: String) -> ClientError {
+        ClientError::Config { message }
+    }
+
+    pub(crate) fn connection(e: reqwest::Error) -> ClientError {
+        ClientError::Connection { message: e.to_string() }
+    }
+
+    pub(crate) fn invalid_response(e: reqwest::Error) -> ClientError {
+        ClientError::InvalidResponse { message: e.to_string() }
+    }
+
+    /// servers predating the structured error model answer with plain text,
+    /// their code is derived from the status instead
+    pub(crate) async fn from_response(res: Response) -> ClientError {
+        let status = res.status();
+        let body = res.text().await.unwrap_or("".to_string());
+
+        match serde_json::from_str::<ErrorResponse>(&body) {
+            Ok(error) => ClientError::Server {
+                status: status.as_u16(),
+                code: error.code,
+                message: error.message,
+                details: error.details
+            },
+            Err(_) => ClientError::Server {
+                status: status.as_u16(),
+                code: match status {
+                    StatusCode::BAD_REQUEST => ErrorCode::BadRequest,
+                    StatusCode::UNAUTHORIZED => ErrorCode::Unauthorized,
+                    StatusCode::FORBIDDEN => ErrorCode::Forbidden,
+                    StatusCode::NOT_FOUND => ErrorCode::NotFound,
+                    StatusCode::UPGRADE_REQUIRED => ErrorCode::UpgradeRequired,
+                    StatusCode::PAYLOAD_TOO_LARGE => ErrorCode::PayloadTooLarge,
+                    StatusCode::UNPROCESSABLE_ENTITY => ErrorCode::UnprocessableEntity,
+                    StatusCode::TOO_MANY_REQUESTS => ErrorCode::TooManyRequests,
+                    StatusCode::INTERNAL_SERVER_ERROR => ErrorCode::Internal,
+                    _ => ErrorCode::Unknown
+                },
+                message: body,
+                details: None
+            }
+        }
+    }
+
+    /// worth another attempt, the request may succeed once the server is back
+    pub fn is_transient(&self) -> bool {
+        match self {
+            ClientError::Connection { .. } => true,
+            ClientError::Server { status, .. } => *status >= 500,
+            _ => false
+        }
+    }
+}
+
+impl Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Config { message } => write!(f, "invalid client configuration: {message}"),
+            ClientError::Connection { message } => write!(f, "could not connect to the server: {message}"),
+            ClientError::InvalidResponse { message } => write!(f, "invalid response from the server: {message}"),
+            ClientError::Incompatible { server_version, min_protocol } => write!(f, "server {server_version} requires protocol {min_protocol}"),
+            ClientError::Server { status, message, details, .. } => {
+                write!(f, "{status}: {message}")?;
+
+                if let Some(details) = details {
+                    write!(f, " ({details})")?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
\ No newline at end of file
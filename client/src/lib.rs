@@ -0,0 +1,7 @@
+//! async client for the LAN Manager server, shared by the app, the CLI and bots
+
+pub mod client;
+pub mod error;
+
+pub use client::{Conditional, LanClient, LanClientBuilder};
+pub use error::ClientError;
\ No newline at end of file
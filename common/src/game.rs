@@ -8,6 +8,13 @@ pub struct Game {
     pub icon: String
 }
 
+/// a game the player added locally, matched by any of its process names
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CustomGame {
+    pub label: String,
+    pub processes: Vec<String>
+}
+
 impl PartialEq for Game {
     fn eq(&self, other: &Self) -> bool {
         self.name == other.name
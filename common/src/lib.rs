@@ -1,2 +1,3 @@
 pub mod game;
+pub mod protocol;
 pub mod response;
\ No newline at end of file
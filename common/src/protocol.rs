@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+pub const CLIENT_VERSION_HEADER: &str = "X-Client-Version";
+pub const PROTOCOL_VERSION_HEADER: &str = "X-Protocol-Version";
+
+/// bumped whenever a change to the shared types breaks older clients
+pub const PROTOCOL_VERSION: u32 = 1;
+/// oldest protocol version the server still accepts
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    History,
+    Leaderboards,
+    Achievements,
+    Players,
+    Leave,
+    Suggestions,
+    UnknownProcesses,
+    Export,
+    Timeline,
+    CoPlay,
+    /// capabilities of newer servers this build does not know about
+    #[serde(other)]
+    Unknown
+}
\ No newline at end of file
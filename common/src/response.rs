@@ -1,2 +1,13 @@
 pub mod now_playing;
-pub mod games;
\ No newline at end of file
+pub mod games;
+pub mod error;
+pub mod history;
+pub mod info;
+pub mod leaderboards;
+pub mod players;
+pub mod suggestions;
+pub mod unknown_processes;
+pub mod export;
+pub mod timeline;
+pub mod coplay;
+pub mod recap;
\ No newline at end of file
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{game::Game, response::now_playing::Player};
+
+/// two players who played the same game at the same time
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CoPlayEdge {
+    /// player id
+    pub source: String,
+    /// player id
+    pub target: String,
+    pub seconds: f64,
+    /// names of the games played together
+    pub games: Vec<String>
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CoPlayGraph {
+    pub nodes: Vec<Player>,
+    pub edges: Vec<CoPlayEdge>
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Buddy {
+    pub player: Player,
+    /// time spent in the same game at the same time
+    pub seconds: f64,
+    /// games both have played, not necessarily together
+    pub games: Vec<Game>
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BuddiesResponse {
+    pub buddies: Vec<Buddy>
+}
\ No newline at end of file
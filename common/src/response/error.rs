@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    BadRequest,
+    Unauthorized,
+    Forbidden,
+    NotFound,
+    UpgradeRequired,
+    PayloadTooLarge,
+    /// the request is well-formed but its content is rejected, e.g. an invalid player name
+    UnprocessableEntity,
+    TooManyRequests,
+    Internal,
+    /// codes of newer servers this build does not know about
+    #[serde(other)]
+    Unknown
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ErrorResponse {
+    pub code: ErrorCode,
+    pub message: String,
+    pub details: Option<String>
+}
\ No newline at end of file
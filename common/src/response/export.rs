@@ -0,0 +1,53 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::{game::Game, response::{history::Session, now_playing::Player}};
+
+/// the tables an export consists of, csv exports contain one of them
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportTable {
+    Sessions,
+    Players,
+    Games
+}
+
+impl ExportTable {
+    pub const ALL: [ExportTable; 3] = [ExportTable::Sessions, ExportTable::Players, ExportTable::Games];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExportTable::Sessions => "sessions",
+            ExportTable::Players => "players",
+            ExportTable::Games => "games"
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PlayerTotal {
+    pub player: Player,
+    /// time played within the exported range
+    pub seconds: f64,
+    pub sessions: usize,
+    pub games: usize
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct GameTotal {
+    pub game: Game,
+    /// time played within the exported range, summed over all players
+    pub seconds: f64,
+    pub sessions: usize,
+    pub players: usize
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ExportResponse {
+    pub from: Option<DateTime<Local>>,
+    pub to: Option<DateTime<Local>>,
+    /// every session overlapping the range, unclipped
+    pub sessions: Vec<Session>,
+    pub players: Vec<PlayerTotal>,
+    pub games: Vec<GameTotal>
+}
\ No newline at end of file
@@ -0,0 +1,17 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::{game::Game, response::now_playing::Player};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Session {
+    pub player: Player,
+    pub game: Game,
+    pub start: DateTime<Local>,
+    pub end: Option<DateTime<Local>>
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HistoryResponse {
+    pub sessions: Vec<Session>
+}
\ No newline at end of file
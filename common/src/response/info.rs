@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+use crate::protocol::Capability;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct InfoResponse {
+    pub version: String,
+    pub protocol: u32,
+    pub min_protocol: u32,
+    pub capabilities: Vec<Capability>
+}
+
+impl InfoResponse {
+    pub fn supports(&self, capability: Capability) -> bool {
+        self.capabilities.contains(&capability)
+    }
+}
\ No newline at end of file
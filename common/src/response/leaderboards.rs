@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+use crate::response::now_playing::Player;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderboardKind {
+    MostHours,
+    MostGames,
+    LongestSession,
+    NightOwl,
+    /// kinds of newer servers this build does not know about, shown with the server's label
+    #[serde(other)]
+    Unknown
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderboardUnit {
+    Seconds,
+    Count
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LeaderboardEntry {
+    pub player: Player,
+    pub value: f64
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Leaderboard {
+    pub kind: LeaderboardKind,
+    pub label: String,
+    pub unit: LeaderboardUnit,
+    pub entries: Vec<LeaderboardEntry>
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LeaderboardsResponse {
+    pub leaderboards: Vec<Leaderboard>
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Achievement {
+    pub id: String,
+    pub label: String,
+    pub description: String,
+    pub unlocked: bool
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AchievementsResponse {
+    pub achievements: Vec<Achievement>
+}
\ No newline at end of file
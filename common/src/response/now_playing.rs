@@ -25,7 +25,10 @@ impl PartialEq for Player {
 #[derive(Serialize, Deserialize, Clone)]
 pub struct NowPlayingEntry {
     pub player: Player,
-    pub games: Vec<String>
+    pub games: Vec<String>,
+    /// the player is online but does not want to share what they play
+    #[serde(default)]
+    pub private: bool
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -37,5 +40,7 @@ pub struct PartyPlayingEntry {
 #[derive(Serialize, Deserialize, Clone)]
 pub struct NowPlayingResponse {
     pub active: Vec<PartyPlayingEntry>,
-    pub online: usize
+    pub online: usize,
+    #[serde(default)]
+    pub private: Vec<Player>
 }
\ No newline at end of file
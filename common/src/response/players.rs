@@ -0,0 +1,19 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::{game::Game, response::now_playing::Player};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PlayerStatus {
+    pub player: Player,
+    pub games: Vec<Game>,
+    pub last_seen: DateTime<Local>,
+    pub version: Option<String>,
+    #[serde(default)]
+    pub private: bool
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PlayersResponse {
+    pub players: Vec<PlayerStatus>
+}
\ No newline at end of file
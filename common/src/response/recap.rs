@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+/// how `/recap` renders the summary
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RecapFormat {
+    #[default]
+    Html,
+    Markdown
+}
+
+impl RecapFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RecapFormat::Html => "html",
+            RecapFormat::Markdown => "markdown"
+        }
+    }
+}
\ No newline at end of file
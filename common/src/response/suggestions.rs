@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+use crate::response::now_playing::Player;
+
+/// body of a player suggesting a game for the catalogue
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SuggestedGame {
+    pub player: Player,
+    pub name: String,
+    pub label: String
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct GameSuggestion {
+    pub name: String,
+    pub label: String,
+    pub suggested_by: Vec<Player>
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SuggestionsResponse {
+    pub suggestions: Vec<GameSuggestion>
+}
+
+/// details an admin provides when adding a suggested or reported game to the catalogue
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct GameApproval {
+    /// defaults to the suggested label or the process name
+    pub label: Option<String>,
+    /// file name in the server's icons directory, required
+    pub icon: Option<String>
+}
\ No newline at end of file
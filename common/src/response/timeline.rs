@@ -0,0 +1,28 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::game::Game;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TimelineEntry {
+    pub game: Game,
+    /// players who played the game at some point during the bucket
+    pub players: usize
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TimelineBucket {
+    pub start: DateTime<Local>,
+    /// players who played anything during the bucket
+    pub online: usize,
+    pub games: Vec<TimelineEntry>
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TimelineResponse {
+    pub from: DateTime<Local>,
+    pub to: DateTime<Local>,
+    /// bucket length in seconds
+    pub step: i64,
+    pub buckets: Vec<TimelineBucket>
+}
\ No newline at end of file
@@ -0,0 +1,46 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::response::now_playing::Player;
+
+/// why a process not in the catalogue looks like a game
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Heuristic {
+    /// the executable lives in a game store's install directory
+    StoreDirectory,
+    HighCpu,
+    HighMemory,
+    /// keeps a render engine of the GPU busy, only detected on Linux
+    GpuHeavy,
+    /// owns the focused fullscreen window, only detected on Linux with X11 or XWayland
+    Fullscreen,
+    #[serde(other)]
+    Unknown
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct UnknownProcess {
+    pub name: String,
+    pub heuristics: Vec<Heuristic>
+}
+
+/// body of an opted-in player reporting processes that might be games
+#[derive(Serialize, Deserialize, Clone)]
+pub struct UnknownProcessesReport {
+    pub player: Player,
+    pub processes: Vec<UnknownProcess>
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct UnknownProcessStats {
+    pub name: String,
+    pub heuristics: Vec<Heuristic>,
+    pub players: Vec<Player>,
+    pub last_seen: DateTime<Local>
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct UnknownProcessesResponse {
+    pub processes: Vec<UnknownProcessStats>
+}
\ No newline at end of file
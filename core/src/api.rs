@@ -0,0 +1,368 @@
+use std::{collections::{HashMap, HashSet}, fmt::{self, Display, Write as _}, fs, path::PathBuf, sync::LazyLock};
+
+use chrono::{DateTime, Local};
+use common::{game::{CustomGame, Game}, protocol::{Capability, PROTOCOL_VERSION}, response::{error::ErrorCode, export::{ExportResponse, ExportTable}, games::GamesResponse, info::InfoResponse, leaderboards::{AchievementsResponse, LeaderboardsResponse}, now_playing::{NowPlayingEntry, NowPlayingResponse, Player}, suggestions::SuggestedGame, unknown_processes::{UnknownProcess, UnknownProcessesReport}}};
+use lan_client::{ClientError, Conditional, LanClient};
+use log::warn;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tokio::sync::Mutex;
+
+use crate::{config::{config_dir, get_or_create_config, Settings}, i18n::{self, Language, Message}};
+
+struct CachedGames {
+    games: GamesResponse,
+    etag: Option<String>
+}
+
+/// rebuilt whenever remote or password in the config change, which also drops `INFO` and `GAMES`
+struct CachedClient {
+    remote: String,
+    password: Option<String>,
+    client: LanClient
+}
+
+static GAMES: LazyLock<Mutex<Option<CachedGames>>> = LazyLock::new(|| Mutex::new(None));
+/// cached icon file by game name
+static ICONS: LazyLock<Mutex<HashMap<String, PathBuf>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+static INFO: LazyLock<Mutex<Option<InfoResponse>>> = LazyLock::new(|| Mutex::new(None));
+static CLIENT: LazyLock<Mutex<Option<CachedClient>>> = LazyLock::new(|| Mutex::new(None));
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ApiError {
+    Config { message: String },
+    Connection { message: String },
+    InvalidResponse { message: String },
+    Incompatible { server_version: String, min_protocol: u32 },
+    Server { status: u16, code: ErrorCode, message: String, details: Option<String> },
+    Internal { message: String }
+}
+
+impl ApiError {
+    pub fn internal(message: String) -> ApiError {
+        ApiError::Internal { message }
+    }
+
+    pub fn config(message: String) -> ApiError {
+        ApiError::Config { message }
+    }
+}
+
+impl From<ClientError> for ApiError {
+    fn from(error: ClientError) -> ApiError {
+        match error {
+            ClientError::Config { message } => ApiError::Config { message },
+            ClientError::Connection { message } => ApiError::Connection { message },
+            ClientError::InvalidResponse { message } => ApiError::InvalidResponse { message },
+            ClientError::Incompatible { server_version, min_protocol } => ApiError::Incompatible { server_version, min_protocol },
+            ClientError::Server { status, code, message, details } => ApiError::Server { status, code, message, details }
+        }
+    }
+}
+
+impl ApiError {
+    pub fn localize(&self, language: Language) -> String {
+        match self {
+            ApiError::Config { message } => format!("{}\n{message}", Message::ConfigError.translate(language)),
+            ApiError::Connection { message } => format!("{}\n{message}", Message::ConnectionError.translate(language)),
+            ApiError::InvalidResponse { message } => format!("{}\n{message}", Message::InvalidResponse.translate(language)),
+            ApiError::Incompatible { server_version, min_protocol } => Message::Incompatible {
+                server_version,
+                min_protocol: *min_protocol,
+                protocol: PROTOCOL_VERSION
+            }.translate(language),
+            ApiError::Server { status, code, message, details } => {
+                let summary = match code {
+                    ErrorCode::BadRequest => Message::BadRequest,
+                    ErrorCode::Unauthorized => Message::Unauthorized,
+                    ErrorCode::Forbidden => Message::Forbidden,
+                    ErrorCode::NotFound => Message::NotFound,
+                    ErrorCode::UpgradeRequired => Message::UpgradeRequired,
+                    ErrorCode::PayloadTooLarge => Message::PayloadTooLarge,
+                    ErrorCode::UnprocessableEntity => Message::InvalidEntry,
+                    ErrorCode::TooManyRequests => Message::RateLimited,
+                    ErrorCode::Internal => Message::ServerError,
+                    ErrorCode::Unknown => Message::UnknownError
+                };
+                let details = details.as_ref()
+                    .map(|d| format!(" ({d})"))
+                    .unwrap_or_default();
+
+                format!("{}\n{status}: {message}{details}", summary.translate(language))
+            },
+            ApiError::Internal { message } => message.clone()
+        }
+    }
+}
+
+/// logs are always english, users see `ErrorMessage` in their configured language
+impl Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.localize(Language::En))
+    }
+}
+
+/// what the frontend receives for failed commands and `poll_error` events
+#[derive(Serialize, Clone)]
+pub struct ErrorMessage {
+    pub error: ApiError,
+    pub message: String
+}
+
+impl From<ApiError> for ErrorMessage {
+    fn from(error: ApiError) -> ErrorMessage {
+        ErrorMessage {
+            message: error.localize(i18n::language()),
+            error
+        }
+    }
+}
+
+fn player(config: &Settings) -> Player {
+    Player {
+        id: config.id.clone(),
+        name: config.name.clone().unwrap_or("unknown".to_string())
+    }
+}
+
+/// the client for the configured server
+pub async fn client() -> Result<LanClient, ApiError> {
+    let config = get_or_create_config(false).map_err(ApiError::config)?;
+    let mut client_lock = CLIENT.lock().await;
+
+    if let Some(cached) = client_lock.as_ref()
+        && cached.remote == config.remote && cached.password == config.password {
+        return Ok(cached.client.clone());
+    }
+
+    let client = LanClient::builder(config.remote.clone())
+        .token(config.password.clone().unwrap_or_default())
+        .client_version(env!("CARGO_PKG_VERSION"))
+        .build()?;
+
+    // capabilities and catalogue belong to the previous server
+    *INFO.lock().await = None;
+    *GAMES.lock().await = None;
+
+    *client_lock = Some(CachedClient {
+        remote: config.remote,
+        password: config.password,
+        client: client.clone()
+    });
+
+    Ok(client)
+}
+
+/// servers predating `/info` are treated as protocol 1 without capabilities
+pub async fn get_info() -> Result<InfoResponse, ApiError> {
+    if let Some(info) = INFO.lock().await.as_ref() {
+        return Ok(info.clone());
+    }
+
+    let info = client().await?.info().await?;
+
+    *INFO.lock().await = Some(info.clone());
+
+    Ok(info)
+}
+
+pub async fn put_now_playing(games: HashSet<String>, private: bool, last_update: DateTime<Local>) -> Result<Option<NowPlayingResponse>, ApiError> {
+    let config = get_or_create_config(false).map_err(ApiError::config)?;
+    let body = NowPlayingEntry {
+        games: games.into_iter().collect(),
+        player: player(&config),
+        private
+    };
+
+    Ok(client().await?.put_now_playing(&body, Some(last_update)).await?)
+}
+
+/// the party as seen by others, without reporting anything
+pub async fn get_now_playing() -> Result<NowPlayingResponse, ApiError> {
+    Ok(client().await?.now_playing().await?)
+}
+
+pub async fn delete_now_playing() -> Result<(), ApiError> {
+    let config = get_or_create_config(false).map_err(ApiError::config)?;
+
+    Ok(client().await?.leave(&config.id).await?)
+}
+
+pub async fn get_games() -> Result<GamesResponse, ApiError> {
+    if let Some(cached) = GAMES.lock().await.as_ref() {
+        return Ok(cached.games.clone());
+    }
+
+    refresh_games().await?;
+
+    GAMES.lock().await.as_ref()
+        .map(|cached| cached.games.clone())
+        .ok_or_else(|| ApiError::internal("games cache empty after refresh".to_string()))
+}
+
+/// revalidates the cached catalogue, returns whether it changed
+pub async fn refresh_games() -> Result<bool, ApiError> {
+    let etag = GAMES.lock().await.as_ref().and_then(|cached| cached.etag.clone());
+
+    match client().await?.games_if_changed(etag.as_deref()).await? {
+        Conditional::NotModified => Ok(false),
+        Conditional::Modified { value: games, etag } => {
+            let mut games_lock = GAMES.lock().await;
+            let changed = games_lock.as_ref().is_none_or(|cached| cached.etag.is_none() || cached.etag != etag);
+
+            *games_lock = Some(CachedGames {
+                games,
+                etag
+            });
+
+            Ok(changed)
+        }
+    }
+}
+
+/// the server catalogue plus the player's custom games, server entries win on conflicts
+pub async fn get_catalogue() -> Result<Vec<Game>, ApiError> {
+    let mut games = get_games().await?.games;
+    let config = get_or_create_config(false).map_err(ApiError::config)?;
+
+    for custom in config.custom_games {
+        for process in custom.processes {
+            let game = Game {
+                name: process,
+                label: custom.label.clone(),
+                icon: String::new()
+            };
+
+            if !games.contains(&game) {
+                games.push(game);
+            }
+        }
+    }
+
+    Ok(games)
+}
+
+/// submits custom games the server does not know yet as catalogue suggestions,
+/// skipping process names suggested with the same label in `previous`, the config before the last save
+pub async fn suggest_custom_games(previous: &[CustomGame]) -> Result<(), ApiError> {
+    if !get_info().await?.supports(Capability::Suggestions) {
+        return Ok(());
+    }
+
+    let known = get_games().await?.games;
+    let config = get_or_create_config(false).map_err(ApiError::config)?;
+    let client = client().await?;
+    let player = player(&config);
+
+    for custom in config.custom_games {
+        for process in custom.processes {
+            let unchanged = previous.iter().any(|p| p.label == custom.label && p.processes.contains(&process));
+
+            if unchanged || known.iter().any(|g| g.name == process) {
+                continue;
+            }
+
+            client.suggest_game(&SuggestedGame {
+                player: player.clone(),
+                name: process,
+                label: custom.label.clone()
+            }).await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn report_unknown_processes(processes: Vec<UnknownProcess>) -> Result<(), ApiError> {
+    let config = get_or_create_config(false).map_err(ApiError::config)?;
+
+    Ok(client().await?.report_unknown_processes(&UnknownProcessesReport {
+        player: player(&config),
+        processes
+    }).await?)
+}
+
+pub async fn get_export(from: Option<DateTime<Local>>, to: Option<DateTime<Local>>) -> Result<ExportResponse, ApiError> {
+    Ok(client().await?.export(from, to).await?)
+}
+
+pub async fn get_export_csv(table: ExportTable, from: Option<DateTime<Local>>, to: Option<DateTime<Local>>) -> Result<String, ApiError> {
+    Ok(client().await?.export_csv(table, from, to).await?)
+}
+
+/// labels are translated by `kind`, the server only sends its own language, unknown kinds keep it
+pub async fn get_leaderboards() -> Result<LeaderboardsResponse, ApiError> {
+    let mut res = client().await?.leaderboards().await?;
+    let language = i18n::language();
+
+    for leaderboard in &mut res.leaderboards {
+        leaderboard.label = Message::LeaderboardLabel { kind: leaderboard.kind, fallback: &leaderboard.label }.translate(language);
+    }
+
+    Ok(res)
+}
+
+/// labels and descriptions are translated by `id`, unknown achievements keep the server's text
+pub async fn get_achievements() -> Result<AchievementsResponse, ApiError> {
+    let config = get_or_create_config(false).map_err(ApiError::config)?;
+    let mut res = client().await?.achievements(&config.id).await?;
+    let language = i18n::language();
+
+    for achievement in &mut res.achievements {
+        achievement.label = Message::AchievementLabel { id: &achievement.id, fallback: &achievement.label }.translate(language);
+        achievement.description = Message::AchievementDescription { id: &achievement.id, fallback: &achievement.description }.translate(language);
+    }
+
+    Ok(res)
+}
+
+/// next to the config, not relative to wherever the client was started
+const ICON_CACHE_DIR: &str = "icons";
+const ICON_SIZE: u32 = 128;
+
+/// stores `bytes` under their hash, so identical icons share a file and names never come from the server
+fn store_icon(bytes: &[u8]) -> Result<PathBuf, String> {
+    let mut hasher = Sha256::new();
+    let mut filename = String::new();
+
+    hasher.update(bytes);
+    write!(filename, "{:x}.png", hasher.finalize()).map_err(|e| e.to_string())?;
+
+    let dir = config_dir().join(ICON_CACHE_DIR);
+    let path = dir.join(filename);
+
+    if !path.exists() {
+        fs::create_dir_all(&dir).map_err(|e| format!("could not create icon cache: {e}"))?;
+        fs::write(&path, bytes).map_err(|e| format!("could not write icon: {e}"))?;
+    }
+
+    fs::canonicalize(&path).map_err(|e| e.to_string())
+}
+
+/// path of the cached icon, revalidated against the server
+pub async fn get_icon(game: &Game) -> Option<String> {
+    let cached = ICONS.lock().await.get(&game.name).cloned();
+    // the server's `ETag` for normalized icons is the hash the file is named after
+    let etag = cached.as_ref()
+        .and_then(|p| p.file_stem())
+        .and_then(|s| s.to_str())
+        .map(|stem| format!("\"{stem}\""));
+    let res = match client().await {
+        Ok(client) => client.game_icon(game, ICON_SIZE, etag.as_deref()).await.map_err(ApiError::from),
+        Err(e) => Err(e)
+    };
+
+    let path = match res {
+        Ok(Conditional::NotModified) => cached?,
+        Ok(Conditional::Modified { value, .. }) => {
+            store_icon(&value).inspect_err(|e| warn!("failed to cache icon of {}: {e}", game.name)).ok()?
+        },
+        // offline or failing server, a stale icon is better than none
+        Err(_) => cached?
+    };
+
+    ICONS.lock().await.insert(game.name.clone(), path.clone());
+
+    path.to_str().map(str::to_owned)
+}
\ No newline at end of file
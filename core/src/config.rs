@@ -1,4 +1,4 @@
-use std::{fs::File, io::Write, fmt::Write as _};
+use std::{env, fs::File, io::Write, fmt::Write as _, path::Path, sync::LazyLock};
 
 use config::Config;
 use log::warn;
@@ -6,13 +6,41 @@ use serde::{Deserialize, Serialize};
 use sha2::{Sha256, Digest};
 use uuid::Uuid;
 
+use common::game::CustomGame;
+
+use crate::i18n::Language;
+
+#[derive(Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Privacy {
+    /// detected games are reported
+    #[default]
+    Public,
+    /// reported as online, but without any games
+    Private,
+    /// nothing is reported, the player appears offline
+    Paused
+}
+
 #[derive(Deserialize, Serialize, Clone)]
 pub struct Settings {
     pub id: String,
     pub remote: String,
     pub name: Option<String>,
     pub autostart: bool,
-    pub password: Option<String>
+    pub password: Option<String>,
+    #[serde(default)]
+    pub language: Language,
+    #[serde(default)]
+    pub privacy: Privacy,
+    /// names of games that are never reported
+    #[serde(default)]
+    pub hidden_games: Vec<String>,
+    #[serde(default)]
+    pub custom_games: Vec<CustomGame>,
+    /// opt-in, reports processes that look like games but are not in the catalogue
+    #[serde(default)]
+    pub report_unknown_processes: bool
 }
 
 pub fn default_config() -> Settings {
@@ -21,16 +49,29 @@ pub fn default_config() -> Settings {
         remote: String::from("https://lan.pein.dev"),
         name: None,
         autostart: true,
-        password: None
+        password: None,
+        language: Language::default(),
+        privacy: Privacy::default(),
+        hidden_games: vec![],
+        custom_games: vec![],
+        report_unknown_processes: false
     }
 }
 
 const PASSWORD_PLACEHOLDER: &str = "(unchanged)";
-pub const CONFIG_PATH: &str = "config.toml";
+/// overridable with `LAN_MANAGER_CONFIG`, e.g. for the CLI running as a daemon
+pub static CONFIG_PATH: LazyLock<String> = LazyLock::new(|| {
+    env::var("LAN_MANAGER_CONFIG").unwrap_or("config.toml".to_string())
+});
+
+/// the directory containing the config, for files stored next to it
+pub fn config_dir() -> &'static Path {
+    Path::new(CONFIG_PATH.as_str()).parent().unwrap_or(Path::new(""))
+}
 
 pub fn get_config(censor: bool) -> Result<Settings, String> {
     Config::builder()
-        .add_source(config::File::with_name(CONFIG_PATH))
+        .add_source(config::File::with_name(CONFIG_PATH.as_str()))
         .build()
         .map_err(|e| e.to_string())?
         .try_deserialize::<Settings>()
@@ -62,7 +103,7 @@ pub fn set_config(config: &Settings) -> Result<(), String> {
     let mut new_config = config.clone();
     new_config.password = password;
 
-    let mut file = File::create(CONFIG_PATH).map_err(|e| e.to_string())?;
+    let mut file = File::create(CONFIG_PATH.as_str()).map_err(|e| e.to_string())?;
     let content = toml::to_string_pretty(&new_config).map_err(|e| e.to_string())?;
 
     file.write(content.as_bytes()).map_err(|e| e.to_string())?;
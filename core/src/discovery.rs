@@ -0,0 +1,500 @@
+use std::{collections::{HashMap, HashSet}, env, fs, path::{Path, PathBuf}};
+
+use common::game::CustomGame;
+use log::{info, warn};
+use serde::Deserialize;
+
+/// how deep game folders are searched for executables
+const MAX_DEPTH: usize = 3;
+
+/// executables shipped next to games that are never the game itself
+pub const IGNORED_EXECUTABLES: [&str; 12] = [
+    "unitycrashhandler",
+    "crashreport",
+    "crashpad_handler",
+    "unins",
+    "uninstall",
+    "setup",
+    "vcredist",
+    "vc_redist",
+    "dxsetup",
+    "dotnet",
+    "easyanticheat",
+    "be_service"
+];
+
+/// a `KeyValues` node as used by Steam's `.vdf` and `.acf` files
+#[derive(Debug)]
+enum Vdf {
+    Value(String),
+    Object(HashMap<String, Vdf>)
+}
+
+impl Vdf {
+    fn get(&self, key: &str) -> Option<&Vdf> {
+        match self {
+            Vdf::Object(map) => map.iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, v)| v),
+            Vdf::Value(_) => None
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Vdf::Value(value) => Some(value),
+            Vdf::Object(_) => None
+        }
+    }
+
+    fn entries(&self) -> impl Iterator<Item = (&String, &Vdf)> {
+        let map = match self {
+            Vdf::Object(map) => Some(map),
+            Vdf::Value(_) => None
+        };
+
+        map.into_iter().flatten()
+    }
+}
+
+fn tokenize(buf: &str) -> Result<Vec<String>, String> {
+    let mut tokens = vec![];
+    let mut chars = buf.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                let mut token = String::new();
+
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => token.push('\n'),
+                            Some('t') => token.push('\t'),
+                            Some(c) => token.push(c),
+                            None => return Err("unterminated escape".to_string())
+                        },
+                        Some(c) => token.push(c),
+                        None => return Err("unterminated string".to_string())
+                    }
+                }
+
+                tokens.push(token);
+            },
+            '{' | '}' => tokens.push(c.to_string()),
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            },
+            c if c.is_whitespace() => {},
+            c => return Err(format!("unexpected character '{c}'"))
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn parse_object(tokens: &mut impl Iterator<Item = String>, nested: bool) -> Result<Vdf, String> {
+    let mut map = HashMap::new();
+
+    while let Some(key) = tokens.next() {
+        if key == "}" {
+            return if nested { Ok(Vdf::Object(map)) } else { Err("unexpected '}'".to_string()) };
+        }
+
+        let value = match tokens.next().as_deref() {
+            Some("{") => parse_object(tokens, true)?,
+            Some("}") | None => return Err(format!("missing value for '{key}'")),
+            Some(value) => Vdf::Value(value.to_string())
+        };
+
+        map.insert(key, value);
+    }
+
+    if nested {
+        Err("unexpected end of file".to_string())
+    } else {
+        Ok(Vdf::Object(map))
+    }
+}
+
+fn parse_vdf(buf: &str) -> Result<Vdf, String> {
+    parse_object(&mut tokenize(buf)?.into_iter(), false)
+}
+
+fn read_vdf(path: &Path) -> Result<Vdf, String> {
+    let buf = fs::read_to_string(path).map_err(|e| format!("could not read {}: {e}", path.display()))?;
+
+    parse_vdf(&buf).map_err(|e| format!("could not parse {}: {e}", path.display()))
+}
+
+fn home_dir() -> Option<PathBuf> {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+}
+
+fn steam_roots(home: Option<&Path>) -> Vec<PathBuf> {
+    let mut roots = vec![];
+
+    if cfg!(windows) {
+        for var in ["ProgramFiles(x86)", "ProgramFiles"] {
+            if let Some(dir) = env::var_os(var) {
+                roots.push(PathBuf::from(dir).join("Steam"));
+            }
+        }
+    }
+
+    if let Some(home) = home {
+        roots.push(home.join(".steam/steam"));
+        roots.push(home.join(".local/share/Steam"));
+        roots.push(home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"));
+        roots.push(home.join("Library/Application Support/Steam"));
+    }
+
+    roots.into_iter()
+        .filter(|r| r.join("steamapps").is_dir())
+        .collect()
+}
+
+/// `steamapps` folders of all libraries, including the one inside the Steam installation
+fn steam_libraries(root: &Path) -> Vec<PathBuf> {
+    let mut libraries = vec![root.join("steamapps")];
+    let path = root.join("steamapps/libraryfolders.vdf");
+
+    match read_vdf(&path) {
+        Ok(vdf) => {
+            // libraries are numbered, older files also keep statistics next to them
+            let folders = vdf.get("libraryfolders").into_iter()
+                .flat_map(Vdf::entries)
+                .filter(|(key, _)| key.parse::<u32>().is_ok());
+
+            for (_, folder) in folders {
+                // older files map the index directly to the path
+                let path = folder.get("path").and_then(Vdf::as_str).or(folder.as_str());
+
+                if let Some(path) = path {
+                    libraries.push(PathBuf::from(path).join("steamapps"));
+                }
+            }
+        },
+        Err(e) => warn!("{e}")
+    }
+
+    libraries
+}
+
+fn is_executable(path: &Path) -> bool {
+    if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("exe")) {
+        return true;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        // scripts and libraries also have the bit set, native games rarely have an extension
+        path.extension().is_none() && fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+    }
+
+    #[cfg(not(unix))]
+    false
+}
+
+fn find_executables(dir: &Path, depth: usize, found: &mut Vec<String>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+
+        if path.is_dir() {
+            if depth < MAX_DEPTH {
+                find_executables(&path, depth + 1, found);
+            }
+
+            continue;
+        }
+
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let lower = name.to_lowercase();
+
+        if is_executable(&path) && !IGNORED_EXECUTABLES.iter().any(|i| lower.starts_with(i)) {
+            found.push(name.to_string());
+        }
+    }
+}
+
+/// games of one `steamapps` folder, read from its `appmanifest_*.acf` files
+fn scan_library(library: &Path) -> Vec<CustomGame> {
+    let Ok(entries) = fs::read_dir(library) else {
+        return vec![];
+    };
+    let mut games = vec![];
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let is_manifest = path.file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with("appmanifest_") && n.ends_with(".acf"));
+
+        if !is_manifest {
+            continue;
+        }
+
+        let manifest = match read_vdf(&path) {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                warn!("{e}");
+                continue;
+            }
+        };
+        let Some(app) = manifest.get("AppState") else {
+            continue;
+        };
+        let (Some(label), Some(install_dir)) = (
+            app.get("name").and_then(Vdf::as_str),
+            app.get("installdir").and_then(Vdf::as_str)
+        ) else {
+            continue;
+        };
+        let mut processes = vec![];
+
+        find_executables(&library.join("common").join(install_dir), 0, &mut processes);
+
+        if !processes.is_empty() {
+            games.push(CustomGame {
+                label: label.to_string(),
+                processes
+            });
+        }
+    }
+
+    games
+}
+
+fn scan_steam(home: Option<&Path>) -> Vec<CustomGame> {
+    steam_roots(home).iter()
+        .flat_map(|r| steam_libraries(r))
+        .filter_map(|l| fs::canonicalize(l).ok())
+        .collect::<HashSet<_>>()
+        .iter()
+        .flat_map(|l| scan_library(l))
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct LegendaryGame {
+    title: String,
+    executable: String
+}
+
+/// Epic games installed through Heroic, which keeps legendary's `installed.json`
+fn scan_heroic(home: &Path) -> Vec<CustomGame> {
+    let paths = [
+        home.join(".config/heroic/legendaryConfig/legendary/installed.json"),
+        home.join(".var/app/com.heroicgameslauncher.hgl/config/heroic/legendaryConfig/legendary/installed.json")
+    ];
+    let mut games = vec![];
+
+    for path in paths.iter().filter(|p| p.is_file()) {
+        let installed = fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|buf| serde_json::from_str::<HashMap<String, LegendaryGame>>(&buf).map_err(|e| e.to_string()));
+
+        match installed {
+            Ok(installed) => games.extend(installed.into_values()
+                .filter_map(|g| Some(CustomGame {
+                    processes: vec![Path::new(&g.executable).file_name()?.to_str()?.to_string()],
+                    label: g.title
+                }))),
+            Err(e) => warn!("could not read {}: {e}", path.display())
+        }
+    }
+
+    games
+}
+
+/// Lutris stores one YAML file per game, the executable is `game.exe`
+fn scan_lutris(home: &Path) -> Vec<CustomGame> {
+    let dirs = [home.join(".config/lutris/games"), home.join(".local/share/lutris/games")];
+    let mut games = vec![];
+
+    for entry in dirs.iter().filter_map(|d| fs::read_dir(d).ok()).flatten().flatten() {
+        let path = entry.path();
+        let Ok(buf) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let mut in_game = false;
+        let mut exe = None;
+
+        for line in buf.lines() {
+            if !line.starts_with(' ') {
+                in_game = line.trim_end() == "game:";
+            } else if in_game && let Some(value) = line.trim().strip_prefix("exe:") {
+                exe = Some(value.trim().trim_matches(['\'', '"']).to_string());
+            }
+        }
+
+        // files are named `<slug>-<id>.yml`
+        let label = path.file_stem()
+            .and_then(|s| s.to_str())
+            .map(|s| s.rsplit_once('-').map_or(s, |(slug, _)| slug).replace('-', " "));
+        let process = exe.as_deref()
+            .and_then(|e| Path::new(e).file_name())
+            .and_then(|n| n.to_str());
+
+        if let (Some(label), Some(process)) = (label, process) {
+            games.push(CustomGame {
+                label,
+                processes: vec![process.to_string()]
+            });
+        }
+    }
+
+    games
+}
+
+/// installed games as proposals for custom games, skipping everything in `known`
+pub fn discover(known: &HashSet<String>) -> Vec<CustomGame> {
+    let home = home_dir();
+    let mut games = scan_steam(home.as_deref());
+
+    if cfg!(target_os = "linux") && let Some(home) = &home {
+        games.extend(scan_heroic(home));
+        games.extend(scan_lutris(home));
+    }
+
+    let games = games.into_iter()
+        .filter_map(|mut g| {
+            g.processes.retain(|p| !known.contains(p));
+            (!g.processes.is_empty()).then_some(g)
+        })
+        .collect::<Vec<_>>();
+
+    info!("discovered {} unknown game(s)", games.len());
+
+    games
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(path: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/discovery").join(path)
+    }
+
+    fn sorted(mut games: Vec<CustomGame>) -> Vec<(String, Vec<String>)> {
+        games.sort_by(|a, b| a.label.cmp(&b.label));
+
+        games.into_iter().map(|g| (g.label, g.processes)).collect()
+    }
+
+    #[test]
+    fn tokenize_handles_escapes_and_comments() {
+        let tokens = tokenize("// header\n\"a\\\"b\" { \"c\\\\d\" \"e\\nf\" } // trailing").unwrap();
+
+        assert_eq!(tokens, ["a\"b", "{", "c\\d", "e\nf", "}"]);
+    }
+
+    #[test]
+    fn tokenize_rejects_unterminated_input() {
+        assert_eq!(tokenize("\"open").unwrap_err(), "unterminated string");
+        assert_eq!(tokenize("\"open\\").unwrap_err(), "unterminated escape");
+        assert_eq!(tokenize("key").unwrap_err(), "unexpected character 'k'");
+    }
+
+    #[test]
+    fn parse_vdf_reads_nested_objects() {
+        let vdf = parse_vdf("\"AppState\" { \"Name\" \"Portal 2\" \"Depots\" { \"621\" \"1\" } }").unwrap();
+        let app = vdf.get("appstate").unwrap();
+
+        assert_eq!(app.get("name").and_then(Vdf::as_str), Some("Portal 2"));
+        assert_eq!(app.get("depots").and_then(|d| d.get("621")).and_then(Vdf::as_str), Some("1"));
+        assert!(app.get("depots").and_then(Vdf::as_str).is_none());
+    }
+
+    #[test]
+    fn parse_vdf_rejects_unbalanced_braces() {
+        assert_eq!(parse_vdf("\"a\" \"b\" }").unwrap_err(), "unexpected '}'");
+        assert_eq!(parse_vdf("\"a\" { \"b\" \"c\"").unwrap_err(), "unexpected end of file");
+        assert_eq!(parse_vdf("\"a\" { \"b\" }").unwrap_err(), "missing value for 'b'");
+        assert_eq!(parse_vdf("\"a\"").unwrap_err(), "missing value for 'a'");
+    }
+
+    #[test]
+    fn steam_libraries_reads_current_format() {
+        let root = fixture("steam");
+        let mut libraries = steam_libraries(&root);
+
+        libraries.sort();
+
+        let mut expected = vec![
+            root.join("steamapps"),
+            PathBuf::from("/home/player/.local/share/Steam/steamapps"),
+            PathBuf::from("D:\\SteamLibrary").join("steamapps")
+        ];
+
+        expected.sort();
+
+        assert_eq!(libraries, expected);
+    }
+
+    #[test]
+    fn steam_libraries_skips_statistics_of_legacy_format() {
+        let root = fixture("steam-legacy");
+
+        assert_eq!(steam_libraries(&root), [root.join("steamapps"), PathBuf::from("/mnt/games/SteamLibrary/steamapps")]);
+    }
+
+    #[test]
+    fn steam_libraries_keeps_own_library_without_file() {
+        let root = fixture("home");
+
+        assert_eq!(steam_libraries(&root), [root.join("steamapps")]);
+    }
+
+    #[test]
+    fn scan_library_reads_manifests() {
+        let games = sorted(scan_library(&fixture("steam/steamapps")));
+
+        // Counter-Strike 2 is listed but not installed, the uninstaller is ignored
+        assert_eq!(games, [("Portal 2".to_string(), vec!["portal2.exe".to_string()])]);
+    }
+
+    #[test]
+    fn scan_heroic_reads_installed_games() {
+        let games = sorted(scan_heroic(&fixture("home")));
+
+        assert_eq!(games, [
+            ("Fortnite".to_string(), vec!["FortniteLauncher.exe".to_string()]),
+            ("Rocket League".to_string(), vec!["RocketLeague.exe".to_string()])
+        ]);
+    }
+
+    #[test]
+    fn scan_lutris_reads_game_executables() {
+        let games = sorted(scan_lutris(&fixture("home")));
+
+        assert_eq!(games, [
+            ("supertuxkart".to_string(), vec!["supertuxkart".to_string()]),
+            ("the witcher 3".to_string(), vec!["witcher3.exe".to_string()])
+        ]);
+    }
+
+    #[test]
+    fn scans_without_launchers_find_nothing() {
+        let home = fixture("steam");
+
+        assert!(scan_heroic(&home).is_empty());
+        assert!(scan_lutris(&home).is_empty());
+    }
+}
\ No newline at end of file
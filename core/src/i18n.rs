@@ -0,0 +1,192 @@
+use common::response::leaderboards::LeaderboardKind;
+use serde::{Deserialize, Serialize};
+
+use crate::config::get_or_create_config;
+
+#[derive(Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    De,
+    En
+}
+
+pub enum Message<'a> {
+    MajorityPlaying { game: &'a str },
+    AchievementUnlocked { label: &'a str, description: &'a str },
+    /// `fallback` is the server's label, for achievements this client doesn't know yet
+    AchievementLabel { id: &'a str, fallback: &'a str },
+    AchievementDescription { id: &'a str, fallback: &'a str },
+    LeaderboardLabel { kind: LeaderboardKind, fallback: &'a str },
+    ConfigError,
+    ConnectionError,
+    InvalidResponse,
+    Incompatible { server_version: &'a str, min_protocol: u32, protocol: u32 },
+    BadRequest,
+    Unauthorized,
+    Forbidden,
+    NotFound,
+    UpgradeRequired,
+    PayloadTooLarge,
+    InvalidEntry,
+    RateLimited,
+    ServerError,
+    UnknownError,
+    TrayPlaying { games: &'a str },
+    TrayNothingDetected,
+    TrayOnline { count: usize },
+    TrayMajority { game: &'a str },
+    TrayPause,
+    TrayPrivate,
+    TrayOpen,
+    TrayQuit
+}
+
+impl Message<'_> {
+    pub fn translate(&self, language: Language) -> String {
+        match language {
+            Language::De => self.de(),
+            Language::En => self.en()
+        }
+    }
+
+    fn de(&self) -> String {
+        match self {
+            Message::MajorityPlaying { game } => format!("Die Mehrheit spielt {game}. Du nicht. Schande!"),
+            Message::AchievementUnlocked { label, description } => format!("Erfolg freigeschaltet: {label}\n{description}"),
+            Message::AchievementLabel { id, fallback } => match *id {
+                "first_game" => "Angekommen",
+                "explorer" => "Entdecker",
+                "marathon" => "Marathon",
+                "night_owl" => "Nachteule",
+                "veteran" => "Veteran",
+                _ => fallback
+            }.to_string(),
+            Message::AchievementDescription { id, fallback } => match *id {
+                "first_game" => "Das erste Spiel auf der LAN gestartet",
+                "explorer" => "5 verschiedene Spiele gespielt",
+                "marathon" => "3 Stunden am Stück dasselbe Spiel gespielt",
+                "night_owl" => "1 Stunde zwischen 0 und 6 Uhr gespielt",
+                "veteran" => "Insgesamt 12 Stunden gespielt",
+                _ => fallback
+            }.to_string(),
+            Message::LeaderboardLabel { kind, fallback } => match kind {
+                LeaderboardKind::MostHours => "Meiste Spielzeit",
+                LeaderboardKind::MostGames => "Meiste Spiele",
+                LeaderboardKind::LongestSession => "Längste Session",
+                LeaderboardKind::NightOwl => "Nachteule",
+                LeaderboardKind::Unknown => fallback
+            }.to_string(),
+            Message::ConfigError => "Konfiguration konnte nicht gelesen werden".to_string(),
+            Message::ConnectionError => "Keine Verbindung zum Server".to_string(),
+            Message::InvalidResponse => "Ungültige Antwort vom Server".to_string(),
+            Message::Incompatible { server_version, min_protocol, protocol } => format!("Client veraltet, bitte aktualisieren\nServer {server_version} erwartet Protokoll {min_protocol}, Client spricht {protocol}"),
+            Message::BadRequest => "Ungültige Anfrage zum Server".to_string(),
+            Message::Unauthorized => "Passwort inkorrekt".to_string(),
+            Message::Forbidden => "Keine Berechtigung".to_string(),
+            Message::NotFound => "Nicht gefunden".to_string(),
+            Message::UpgradeRequired => "Client veraltet, bitte aktualisieren".to_string(),
+            Message::PayloadTooLarge => "Anfrage zu groß für den Server".to_string(),
+            Message::InvalidEntry => "Vom Server abgelehnt, bitte Namen in den Einstellungen prüfen".to_string(),
+            Message::RateLimited => "Zu viele Anfragen, bitte kurz warten".to_string(),
+            Message::ServerError => "Interner Fehler des Servers".to_string(),
+            Message::UnknownError => "Unbekannter Fehler".to_string(),
+            Message::TrayPlaying { games } => format!("Du spielst: {games}"),
+            Message::TrayNothingDetected => "Kein Spiel erkannt".to_string(),
+            Message::TrayOnline { count } => format!("{count} Spieler online"),
+            Message::TrayMajority { game } => format!("Die Mehrheit spielt {game}"),
+            Message::TrayPause => "Übertragung pausieren".to_string(),
+            Message::TrayPrivate => "Privatmodus".to_string(),
+            Message::TrayOpen => "Öffnen".to_string(),
+            Message::TrayQuit => "Beenden".to_string()
+        }
+    }
+
+    fn en(&self) -> String {
+        match self {
+            Message::MajorityPlaying { game } => format!("The majority is playing {game}. You are not. Shame!"),
+            Message::AchievementUnlocked { label, description } => format!("Achievement unlocked: {label}\n{description}"),
+            Message::AchievementLabel { id, fallback } => match *id {
+                "first_game" => "Arrived",
+                "explorer" => "Explorer",
+                "marathon" => "Marathon",
+                "night_owl" => "Night Owl",
+                "veteran" => "Veteran",
+                _ => fallback
+            }.to_string(),
+            Message::AchievementDescription { id, fallback } => match *id {
+                "first_game" => "Started the first game at the LAN",
+                "explorer" => "Played 5 different games",
+                "marathon" => "Played the same game for 3 hours straight",
+                "night_owl" => "Played for 1 hour between midnight and 6 am",
+                "veteran" => "Played for 12 hours in total",
+                _ => fallback
+            }.to_string(),
+            Message::LeaderboardLabel { kind, fallback } => match kind {
+                LeaderboardKind::MostHours => "Most playtime",
+                LeaderboardKind::MostGames => "Most games",
+                LeaderboardKind::LongestSession => "Longest session",
+                LeaderboardKind::NightOwl => "Night owl",
+                LeaderboardKind::Unknown => fallback
+            }.to_string(),
+            Message::ConfigError => "Could not read configuration".to_string(),
+            Message::ConnectionError => "Could not connect to server".to_string(),
+            Message::InvalidResponse => "Invalid response from server".to_string(),
+            Message::Incompatible { server_version, min_protocol, protocol } => format!("Client outdated, please update\nServer {server_version} requires protocol {min_protocol}, client speaks {protocol}"),
+            Message::BadRequest => "Invalid request to server".to_string(),
+            Message::Unauthorized => "Incorrect password".to_string(),
+            Message::Forbidden => "Access denied".to_string(),
+            Message::NotFound => "Not found".to_string(),
+            Message::UpgradeRequired => "Client outdated, please update".to_string(),
+            Message::PayloadTooLarge => "Request too large for the server".to_string(),
+            Message::InvalidEntry => "Rejected by the server, please check your name in the settings".to_string(),
+            Message::RateLimited => "Too many requests, please wait a moment".to_string(),
+            Message::ServerError => "Internal server error".to_string(),
+            Message::UnknownError => "Unknown error".to_string(),
+            Message::TrayPlaying { games } => format!("You are playing: {games}"),
+            Message::TrayNothingDetected => "No game detected".to_string(),
+            Message::TrayOnline { count } => format!("{count} player(s) online"),
+            Message::TrayMajority { game } => format!("The majority is playing {game}"),
+            Message::TrayPause => "Pause reporting".to_string(),
+            Message::TrayPrivate => "Private mode".to_string(),
+            Message::TrayOpen => "Open".to_string(),
+            Message::TrayQuit => "Quit".to_string()
+        }
+    }
+}
+
+/// the language configured in `Settings`, falling back to the default if the config is unreadable
+pub fn language() -> Language {
+    get_or_create_config(false)
+        .map(|c| c.language)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn achievements_are_translated_by_id() {
+        let label = Message::AchievementLabel { id: "night_owl", fallback: "Nachteule" };
+
+        assert_eq!(label.translate(Language::En), "Night Owl");
+        assert_eq!(label.translate(Language::De), "Nachteule");
+    }
+
+    #[test]
+    fn unknown_achievements_keep_the_server_text() {
+        let description = Message::AchievementDescription { id: "speedrun", fallback: "Schnell gespielt" };
+
+        assert_eq!(description.translate(Language::En), "Schnell gespielt");
+    }
+
+    #[test]
+    fn unknown_leaderboards_keep_the_server_label() {
+        let kind: LeaderboardKind = serde_json::from_str("\"most_kills\"").unwrap();
+
+        assert!(kind == LeaderboardKind::Unknown);
+        assert_eq!(Message::LeaderboardLabel { kind, fallback: "Meiste Kills" }.translate(Language::En), "Meiste Kills");
+        assert_eq!(Message::LeaderboardLabel { kind: LeaderboardKind::MostHours, fallback: "Meiste Spielzeit" }.translate(Language::En), "Most playtime");
+    }
+}
\ No newline at end of file
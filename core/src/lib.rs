@@ -0,0 +1,6 @@
+pub mod api;
+pub mod config;
+pub mod i18n;
+pub mod processes;
+pub mod discovery;
+pub mod telemetry;
\ No newline at end of file
@@ -0,0 +1,168 @@
+use std::{collections::HashSet, sync::LazyLock};
+
+use chrono::{DateTime, Local, TimeDelta};
+use common::{game::Game, response::now_playing::{NowPlayingResponse, PartyPlayingEntry}};
+use log::{info, warn};
+use serde::Serialize;
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+use tokio::sync::Mutex;
+
+use crate::{api::{delete_now_playing, get_catalogue, get_info, put_now_playing, ApiError}, config::{self, Privacy}};
+
+#[derive(Serialize, Clone)]
+struct Process {
+    name: String,
+    exe: Option<String>,
+    cmd: Vec<String>,
+    cwd: Option<String>
+}
+
+pub struct ReportState {
+    pub last_response: Option<NowPlayingResponse>,
+    pub last_put: DateTime<Local>,
+    pub detected: Vec<Game>
+}
+
+pub static STATE: LazyLock<Mutex<ReportState>> = LazyLock::new(|| Mutex::new(ReportState {
+    last_put: Local::now() - TimeDelta::days(300),
+    last_response: None,
+    detected: vec![]
+}));
+
+/// outcome of a single `report`
+pub struct Report {
+    /// catalogue games running on this machine, including hidden ones
+    pub detected: Vec<Game>,
+    /// games actually transmitted, `None` if reporting is paused
+    pub reported: Option<HashSet<String>>,
+    /// what the server answered, `None` if paused or nothing changed since the last report
+    pub response: Option<NowPlayingResponse>
+}
+
+pub async fn set_privacy(privacy: Privacy) -> Result<(), String> {
+    let mut config = config::get_or_create_config(true)?;
+
+    config.privacy = privacy;
+    config::set_config(&config)?;
+
+    // leave right away instead of waiting for the heartbeat to expire
+    if privacy == Privacy::Paused && let Err(e) = delete_now_playing().await {
+        warn!("failed to leave the party: {e}");
+    }
+
+    Ok(())
+}
+
+/// the most played game, if at least half of the online players play it
+pub fn majority(response: &NowPlayingResponse) -> Option<PartyPlayingEntry> {
+    let mut active = response.active.clone();
+
+    active.sort_by(|a, b| {
+        if a.players.len() != b.players.len() {
+            return b.players.len().cmp(&a.players.len());
+        }
+
+        a.game.label.cmp(&b.game.label)
+    });
+
+    active.into_iter()
+        .next()
+        .filter(|most_played| most_played.players.len() >= response.online / 2)
+}
+
+fn get_processes() -> Vec<Process> {
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing()
+            .with_exe(UpdateKind::Always)
+            .with_cwd(UpdateKind::Always)
+            .with_cmd(UpdateKind::Always)
+    );
+
+    system.processes()
+        .values()
+        .filter_map(|p| p.name().to_str().map(|name| Process {
+                name: String::from(name),
+                exe: p.exe()
+                    .and_then(|e| e.to_str()).map(String::from),
+                cmd: p.cmd().iter()
+                    .filter_map(|s| s.to_str())
+                    .map(String::from)
+                    .collect(),
+                cwd: p.cwd()
+                    .and_then(|e| e.to_str()).map(String::from)
+            })
+        )
+        .collect()
+}
+
+/// detects running games and transmits them according to the configured privacy
+pub async fn report() -> Result<Report, ApiError> {
+    let processes = get_processes();
+
+    get_info().await?;
+    let games = get_catalogue().await?;
+
+    let open_games: HashSet<String> = processes.into_iter()
+        .map(|p| p.name)
+        .filter(|p| games.iter().any(|g| &g.name == p))
+        .collect();
+    let detected = games.iter()
+        .filter(|g| open_games.contains(&g.name))
+        .cloned()
+        .collect::<Vec<Game>>();
+
+    STATE.lock().await.detected = detected.clone();
+
+    let config = config::get_or_create_config(false).map_err(ApiError::config)?;
+    let reported: HashSet<String> = match config.privacy {
+        Privacy::Public => open_games.iter()
+            .filter(|g| !config.hidden_games.contains(g))
+            .cloned()
+            .collect(),
+        Privacy::Private => HashSet::new(),
+        Privacy::Paused => {
+            info!("reporting paused, not transmitting activity");
+
+            return Ok(Report {
+                detected,
+                reported: None,
+                response: None
+            });
+        }
+    };
+
+    info!("transmitting activity: {}", if reported.is_empty() { "(nothing)".to_string() } else { reported.iter().cloned().collect::<Vec<String>>().join(", ") });
+
+    let last_put = STATE.lock().await.last_put;
+    let response = match put_now_playing(reported.clone(), config.privacy == Privacy::Private, last_put).await {
+        Ok(Some(others_playing)) => {
+            info!("received activity from {} player(s)", others_playing.online);
+
+            let mut state_lock = STATE.lock().await;
+
+            state_lock.last_put = Local::now();
+            state_lock.last_response = Some(others_playing.clone());
+
+            Some(others_playing)
+        },
+        Ok(None) => {
+            info!("received not modified");
+
+            None
+        },
+        Err(e) => {
+            warn!("error transmitting activity: {e}");
+
+            return Err(e);
+        }
+    };
+
+    Ok(Report {
+        detected,
+        reported: Some(reported),
+        response
+    })
+}
\ No newline at end of file
@@ -0,0 +1,145 @@
+use std::{collections::HashMap, thread, time::{Duration, Instant}};
+
+use common::{protocol::Capability, response::unknown_processes::{Heuristic, UnknownProcess}};
+use log::info;
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind, MINIMUM_CPU_UPDATE_INTERVAL};
+
+use crate::{api::{self, get_catalogue, get_info, ApiError}, config::{self, Privacy}, discovery::IGNORED_EXECUTABLES};
+
+mod fullscreen;
+mod gpu;
+
+/// percent of a single core
+const HIGH_CPU: f32 = 25.0;
+/// percent of a render engine
+const HIGH_GPU: f64 = 20.0;
+/// GPU time is measured over this long, a single frame would say little
+const SAMPLE_DURATION: Duration = Duration::from_secs(1);
+const HIGH_MEMORY: u64 = 1024 * 1024 * 1024;
+/// lowercase path fragments of launcher install directories
+const STORE_DIRECTORIES: [&str; 9] = [
+    "steamapps",
+    "epic games",
+    "gog galaxy",
+    "gog games",
+    "heroic",
+    "lutris",
+    "ubisoft game launcher",
+    "ea games",
+    "riot games"
+];
+
+/// heuristics of every running process by name, blocks while cpu usage is measured
+fn sample() -> HashMap<String, Vec<Heuristic>> {
+    let mut system = System::new();
+    let refresh_kind = ProcessRefreshKind::nothing()
+        .with_cpu()
+        .with_memory()
+        .with_exe(UpdateKind::OnlyIfNotSet);
+
+    // cpu and gpu usage are the difference between two refreshes
+    let gpu_before = gpu::busy();
+    let started = Instant::now();
+
+    system.refresh_processes_specifics(ProcessesToUpdate::All, true, refresh_kind);
+    thread::sleep(SAMPLE_DURATION.max(MINIMUM_CPU_UPDATE_INTERVAL));
+    system.refresh_processes_specifics(ProcessesToUpdate::All, true, refresh_kind);
+
+    let gpu_usage = gpu::usage(&gpu_before, &gpu::busy(), started.elapsed());
+    let fullscreen = fullscreen::process();
+    let mut processes: HashMap<String, Vec<Heuristic>> = HashMap::new();
+
+    for (pid, process) in system.processes() {
+        let Some(name) = process.name().to_str() else {
+            continue;
+        };
+        let exe = process.exe()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase().replace('\\', "/"))
+            .unwrap_or_default();
+        let heuristics = processes.entry(name.to_string()).or_default();
+        let mut add = |heuristic| if !heuristics.contains(&heuristic) {
+            heuristics.push(heuristic);
+        };
+
+        if STORE_DIRECTORIES.iter().any(|d| exe.contains(d)) {
+            add(Heuristic::StoreDirectory);
+        }
+
+        if process.cpu_usage() >= HIGH_CPU {
+            add(Heuristic::HighCpu);
+        }
+
+        if process.memory() >= HIGH_MEMORY {
+            add(Heuristic::HighMemory);
+        }
+
+        if gpu_usage.get(&pid.as_u32()).is_some_and(|usage| *usage >= HIGH_GPU) {
+            add(Heuristic::GpuHeavy);
+        }
+
+        if fullscreen == Some(pid.as_u32()) {
+            add(Heuristic::Fullscreen);
+        }
+    }
+
+    processes
+}
+
+/// installed by a launcher or at least two signs of load, a single one also fits browsers and compilers
+fn is_candidate(heuristics: &[Heuristic]) -> bool {
+    let load = [Heuristic::GpuHeavy, Heuristic::Fullscreen, Heuristic::HighCpu, Heuristic::HighMemory].iter()
+        .filter(|h| heuristics.contains(h))
+        .count();
+
+    heuristics.contains(&Heuristic::StoreDirectory) || load >= 2
+}
+
+/// reports running processes that look like games but are not in the catalogue, only if opted in
+pub async fn poll() -> Result<(), ApiError> {
+    let config = config::get_or_create_config(false).map_err(ApiError::config)?;
+
+    if !config.report_unknown_processes || config.privacy != Privacy::Public {
+        return Ok(());
+    }
+
+    if !get_info().await?.supports(Capability::UnknownProcesses) {
+        return Ok(());
+    }
+
+    let games = get_catalogue().await?;
+    let sampled = tokio::task::spawn_blocking(sample).await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+    let candidates = sampled.into_iter()
+        .filter(|(name, heuristics)| is_candidate(heuristics)
+            && !games.iter().any(|g| &g.name == name)
+            && !config.hidden_games.contains(name)
+            && !IGNORED_EXECUTABLES.iter().any(|i| name.to_lowercase().starts_with(i)))
+        .map(|(name, heuristics)| UnknownProcess {
+            name,
+            heuristics
+        })
+        .collect::<Vec<_>>();
+
+    if candidates.is_empty() {
+        return Ok(());
+    }
+
+    info!("reporting {} unknown process(es)", candidates.len());
+    api::report_unknown_processes(candidates).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn candidates_need_a_launcher_or_two_signs_of_load() {
+        assert!(is_candidate(&[Heuristic::StoreDirectory]));
+        assert!(is_candidate(&[Heuristic::GpuHeavy, Heuristic::Fullscreen]));
+        assert!(is_candidate(&[Heuristic::HighCpu, Heuristic::HighMemory]));
+        assert!(!is_candidate(&[Heuristic::Fullscreen]));
+        assert!(!is_candidate(&[Heuristic::HighCpu, Heuristic::Unknown]));
+        assert!(!is_candidate(&[]));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,28 @@
+//! the process owning the focused fullscreen window, only on Linux with X11 or XWayland
+
+#[cfg(target_os = "linux")]
+pub fn process() -> Option<u32> {
+    use x11rb::{connection::Connection, protocol::xproto::{Atom, AtomEnum, ConnectionExt, Window}};
+
+    let (conn, screen) = x11rb::connect(None).ok()?;
+    let root = conn.setup().roots.get(screen)?.root;
+    let atom = |name: &str| conn.intern_atom(false, name.as_bytes()).ok()?.reply().ok().map(|r| r.atom);
+    let property = |window: Window, name: Atom, kind: AtomEnum, length: u32| conn.get_property(false, window, name, kind, 0, length).ok()?
+        .reply().ok()?
+        .value32()
+        .map(|values| values.collect::<Vec<u32>>());
+
+    let window = *property(root, atom("_NET_ACTIVE_WINDOW")?, AtomEnum::WINDOW, 1)?.first()?;
+    let fullscreen = atom("_NET_WM_STATE_FULLSCREEN")?;
+
+    if !property(window, atom("_NET_WM_STATE")?, AtomEnum::ATOM, 32)?.contains(&fullscreen) {
+        return None;
+    }
+
+    property(window, atom("_NET_WM_PID")?, AtomEnum::CARDINAL, 1)?.first().copied()
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn process() -> Option<u32> {
+    None
+}
\ No newline at end of file
@@ -0,0 +1,136 @@
+//! GPU time per process from the DRM `fdinfo` of Linux 5.19 and newer, other platforms report nothing
+
+use std::{collections::HashMap, time::Duration};
+
+/// engines that render frames, video decoding and copies are left out
+const RENDER_ENGINES: [&str; 2] = ["gfx", "render"];
+
+/// busy nanoseconds per DRM client and engine, by process id
+pub type Busy = HashMap<u32, HashMap<String, u64>>;
+
+/// adds the render engines of one `fdinfo` file, keyed by device, client and engine
+fn parse_fdinfo(info: &str, engines: &mut HashMap<String, u64>) {
+    let mut client = None;
+    let mut device = "";
+    let mut times = vec![];
+
+    for line in info.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+
+        match key {
+            "drm-client-id" => client = Some(value),
+            "drm-pdev" => device = value,
+            _ => if let Some(engine) = key.strip_prefix("drm-engine-")
+                && RENDER_ENGINES.contains(&engine)
+                && let Some(ns) = value.strip_suffix(" ns").and_then(|v| v.parse::<u64>().ok()) {
+                times.push((engine, ns));
+            }
+        }
+    }
+
+    // several descriptors may share a client, the key keeps it from being counted twice
+    if let Some(client) = client {
+        for (engine, ns) in times {
+            engines.insert(format!("{device}/{client}/{engine}"), ns);
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn busy() -> Busy {
+    use std::{fs, path::Path};
+
+    let mut busy = Busy::new();
+    let Ok(processes) = fs::read_dir("/proc") else {
+        return busy;
+    };
+
+    for process in processes.flatten() {
+        let Some(pid) = process.file_name().to_str().and_then(|p| p.parse::<u32>().ok()) else {
+            continue;
+        };
+        let Ok(fds) = fs::read_dir(process.path().join("fd")) else {
+            continue;
+        };
+
+        for fd in fds.flatten() {
+            // only render nodes and cards have DRM statistics, everything else is skipped cheaply
+            if !fs::read_link(fd.path()).is_ok_and(|target| target.starts_with("/dev/dri")) {
+                continue;
+            }
+
+            let info = Path::new("/proc").join(pid.to_string()).join("fdinfo").join(fd.file_name());
+
+            if let Ok(info) = fs::read_to_string(info) {
+                parse_fdinfo(&info, busy.entry(pid).or_default());
+            }
+        }
+    }
+
+    busy.retain(|_, engines| !engines.is_empty());
+
+    busy
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn busy() -> Busy {
+    Busy::new()
+}
+
+/// percent of its busiest engine each process used between `before` and `after`
+pub fn usage(before: &Busy, after: &Busy, elapsed: Duration) -> HashMap<u32, f64> {
+    let elapsed = elapsed.as_nanos().max(1) as f64;
+
+    after.iter()
+        .map(|(pid, engines)| {
+            let busiest = engines.iter()
+                .map(|(key, ns)| {
+                    // clients opened in between have no baseline
+                    let start = before.get(pid).and_then(|e| e.get(key)).copied().unwrap_or(*ns);
+
+                    ns.saturating_sub(start)
+                })
+                .max()
+                .unwrap_or_default();
+
+            (*pid, busiest as f64 / elapsed * 100.0)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AMDGPU: &str = "pos:\t0\nflags:\t02100002\ndrm-driver:\tamdgpu\ndrm-client-id:\t42\ndrm-pdev:\t0000:03:00.0\ndrm-memory-vram:\t524288 KiB\ndrm-engine-gfx:\t2000000 ns\ndrm-engine-dec:\t900000000 ns\n";
+
+    #[test]
+    fn parse_fdinfo_reads_render_engines() {
+        let mut engines = HashMap::new();
+
+        parse_fdinfo(AMDGPU, &mut engines);
+        parse_fdinfo("drm-driver:\ti915\ndrm-client-id:\t7\ndrm-pdev:\t0000:00:02.0\ndrm-engine-render:\t5 ns\ndrm-engine-capacity-render:\t1\n", &mut engines);
+        parse_fdinfo("pos:\t0\nflags:\t0100002\n", &mut engines);
+
+        assert_eq!(engines, HashMap::from([
+            ("0000:03:00.0/42/gfx".to_string(), 2_000_000),
+            ("0000:00:02.0/7/render".to_string(), 5)
+        ]));
+    }
+
+    #[test]
+    fn usage_is_relative_to_the_busiest_engine() {
+        let before = Busy::from([(1, HashMap::from([("a/1/gfx".to_string(), 100_000_000), ("b/1/gfx".to_string(), 0)]))]);
+        let after = Busy::from([
+            (1, HashMap::from([("a/1/gfx".to_string(), 600_000_000), ("b/1/gfx".to_string(), 100_000_000)])),
+            (2, HashMap::from([("a/2/gfx".to_string(), 900_000_000)]))
+        ]);
+        let usage = usage(&before, &after, Duration::from_secs(1));
+
+        assert_eq!(usage[&1], 50.0);
+        assert_eq!(usage[&2], 0.0);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,215 @@
+const params = new URLSearchParams(window.location.search);
+const refreshSeconds = Number(params.get("refresh")) || 15;
+const hours = Number(params.get("hours")) || 24;
+// 10 minute buckets for the default 24 hours, longer ones for longer ranges so the server limit of 2000 is never hit
+const bucketMinutes = Math.max(10, Math.ceil(hours * 60 / 144));
+const timelineGames = 6;
+
+if (params.has("kiosk")) {
+    document.body.classList.add("kiosk");
+}
+
+document.getElementById("kiosk").addEventListener("click", () => {
+    document.body.classList.add("kiosk");
+    document.documentElement.requestFullscreen?.();
+});
+
+function element(tag, className, text) {
+    const el = document.createElement(tag);
+
+    if (className) {
+        el.className = className;
+    }
+
+    if (text !== undefined) {
+        el.textContent = text;
+    }
+
+    return el;
+}
+
+function formatHours(ms) {
+    const minutes = Math.round(ms / 60000);
+
+    return `${Math.floor(minutes / 60)}h ${String(minutes % 60).padStart(2, "0")}m`;
+}
+
+function duration(session, now) {
+    const end = session.end ? new Date(session.end) : now;
+
+    return end - new Date(session.start);
+}
+
+function sumBy(sessions, key, now) {
+    const totals = new Map();
+
+    for (const session of sessions) {
+        const [id, label] = key(session);
+        const total = totals.get(id) || { label, ms: 0 };
+
+        total.ms += duration(session, now);
+        totals.set(id, total);
+    }
+
+    return [...totals.values()].sort((a, b) => b.ms - a.ms);
+}
+
+function renderNowPlaying(nowPlaying) {
+    const container = document.getElementById("now-playing");
+    const entries = nowPlaying.active
+        .sort((a, b) => b.players.length - a.players.length || a.game.label.localeCompare(b.game.label));
+
+    container.replaceChildren();
+
+    if (entries.length === 0) {
+        container.append(element("p", "empty", "(niemand spielt etwas)"));
+        return;
+    }
+
+    for (const entry of entries) {
+        const card = element("div", "card");
+        const icon = element("img");
+        const text = element("div");
+
+        icon.src = `api/v1/games/${encodeURIComponent(entry.game.name)}/icon`;
+        text.append(
+            element("div", "label", entry.game.label),
+            element("div", "players", entry.players.map(p => p.name).sort().join(", "))
+        );
+        card.append(icon, text, element("div", "count", entry.players.length));
+        container.append(card);
+    }
+}
+
+function formatValue(value, unit) {
+    return unit === "seconds" ? formatHours(value * 1000) : String(value);
+}
+
+function renderLeaderboards(leaderboards) {
+    const container = document.getElementById("leaderboards");
+
+    container.replaceChildren();
+
+    for (const leaderboard of leaderboards.filter(l => l.entries.length > 0)) {
+        const list = element("ol");
+
+        for (const entry of leaderboard.entries.slice(0, 5)) {
+            const item = element("li");
+
+            item.append(element("span", "", entry.player.name), element("span", "", formatValue(entry.value, leaderboard.unit)));
+            list.append(item);
+        }
+
+        container.append(element("h2", "", leaderboard.label), list);
+    }
+}
+
+function renderTimeline(timeline) {
+    const svg = document.getElementById("timeline");
+    const legend = document.getElementById("timeline-legend");
+    const totals = new Map();
+
+    for (const bucket of timeline.buckets) {
+        for (const entry of bucket.games) {
+            const total = totals.get(entry.game.name) || { label: entry.game.label, players: 0 };
+
+            total.players += entry.players;
+            totals.set(entry.game.name, total);
+        }
+    }
+
+    const games = [...totals.entries()]
+        .sort((a, b) => b[1].players - a[1].players)
+        .slice(0, timelineGames)
+        .map(([name, total]) => ({ name, label: total.label }));
+    const series = games.map(game => timeline.buckets.map(bucket => bucket.games.find(e => e.game.name === game.name)?.players || 0));
+    const others = timeline.buckets.map((bucket, i) => bucket.games.reduce((sum, e) => sum + e.players, 0) - series.reduce((sum, s) => sum + s[i], 0));
+
+    if (others.some(c => c > 0)) {
+        games.push({ name: "", label: "Andere" });
+        series.push(others);
+    }
+
+    const max = Math.max(1, ...timeline.buckets.map((_, i) => series.reduce((sum, s) => sum + s[i], 0)));
+    const width = 800 / Math.max(1, timeline.buckets.length - 1);
+    const baseline = timeline.buckets.map(() => 0);
+    const x = i => (i * width).toFixed(1);
+    const y = c => (200 - c / max * 190).toFixed(1);
+
+    svg.replaceChildren();
+    legend.replaceChildren();
+
+    series.forEach((counts, index) => {
+        const path = document.createElementNS("http://www.w3.org/2000/svg", "path");
+        const top = counts.map((c, i) => baseline[i] + c);
+        const upper = top.map((c, i) => `${x(i)},${y(c)}`);
+        const lower = baseline.map((c, i) => `${x(i)},${y(c)}`).reverse();
+        const color = `hsl(${index * 67 % 360}, 60%, 55%)`;
+
+        path.setAttribute("d", `M${upper.join(" L")} L${lower.join(" L")} Z`);
+        path.style.fill = color;
+        svg.append(path);
+        top.forEach((c, i) => baseline[i] = c);
+
+        const item = element("span", "", games[index].label);
+
+        item.style.setProperty("--color", color);
+        legend.append(item);
+    });
+}
+
+function renderGames(sessions, now) {
+    const container = document.getElementById("games");
+    const totals = sumBy(sessions, s => [s.game.name, s.game.label], now).slice(0, 10);
+    const max = Math.max(1, ...totals.map(t => t.ms));
+
+    container.replaceChildren();
+
+    for (const total of totals) {
+        const bar = element("div", "bar");
+        const fill = element("div", "fill");
+
+        fill.style.width = `${total.ms / max * 100}%`;
+        bar.append(element("span", "", total.label), fill, element("span", "", formatHours(total.ms)));
+        container.append(bar);
+    }
+}
+
+async function fetchJson(url) {
+    const res = await fetch(url);
+
+    if (!res.ok) {
+        throw new Error(`${res.status}: ${await res.text()}`);
+    }
+
+    return res.json();
+}
+
+async function refresh() {
+    const now = new Date();
+    const from = new Date(now.getTime() - hours * 3600000);
+    const error = document.getElementById("error");
+
+    document.getElementById("clock").textContent = now.toLocaleTimeString("de-DE", { hour: "2-digit", minute: "2-digit" });
+
+    try {
+        const [nowPlaying, { sessions }, { leaderboards }, timeline] = await Promise.all([
+            fetchJson("api/v1/now-playing"),
+            fetchJson(`api/v1/history?from=${encodeURIComponent(from.toISOString())}`),
+            fetchJson("api/v1/leaderboards"),
+            fetchJson(`api/v1/timeline?from=${encodeURIComponent(from.toISOString())}&step=${bucketMinutes * 60}`)
+        ]);
+
+        document.getElementById("online").textContent = `${nowPlaying.online} online`;
+        renderNowPlaying(nowPlaying);
+        renderLeaderboards(leaderboards);
+        renderTimeline(timeline);
+        renderGames(sessions, now);
+        error.textContent = "";
+    } catch (e) {
+        error.textContent = `Fehler beim Laden: ${e.message}`;
+    }
+}
+
+refresh();
+setInterval(refresh, refreshSeconds * 1000);
@@ -1,19 +1,41 @@
 use std::{collections::HashMap, sync::Arc};
 
-use actix_web::{Scope, web::{self, Data}};
+use actix_web::{HttpRequest, Result, Scope, dev::HttpServiceFactory, error::{JsonPayloadError, PathError, QueryPayloadError}, middleware::from_fn, web::{self, Data}};
+use actix_web_httpauth::extractors::bearer::BearerAuth;
 use chrono::{DateTime, Local};
+use common::response::{suggestions::GameSuggestion, unknown_processes::UnknownProcessStats};
 use tokio::sync::Mutex;
 
-use crate::{metrics::MetricsContext, repo::now_playing::NowPlayingInfo};
+use crate::{api::{error::ApiError, rate_limit::RateLimiter}, config::{ADMIN_PASSWORD, MAX_BODY_BYTES, PASSWORD, PUBLIC_READ, RATE_LIMIT_ADDRESS_BURST, RATE_LIMIT_ADDRESS_PER_MINUTE, RATE_LIMIT_BURST, RATE_LIMIT_PER_MINUTE}, events::EventBus, metrics::MetricsContext, repo::{history::History, now_playing::{NowPlayingInfo, PartyState}}};
 
+pub mod error;
+mod rate_limit;
 mod now_playing;
 mod games;
+mod history;
+mod leaderboards;
+mod players;
+mod protocol;
+mod suggestions;
+mod unknown_processes;
+mod export;
+mod recap;
+mod timeline;
+mod coplay;
 
 #[derive(Clone)]
 pub struct SharedData {
     pub store: Arc<Mutex<HashMap<String, NowPlayingInfo>>>,
     pub last_update: Arc<Mutex<DateTime<Local>>>,
-    pub metrics: MetricsContext
+    pub history: Arc<Mutex<History>>,
+    pub suggestions: Arc<Mutex<Vec<GameSuggestion>>>,
+    pub unknown_processes: Arc<Mutex<Vec<UnknownProcessStats>>>,
+    pub metrics: MetricsContext,
+    pub events: EventBus,
+    pub party: Arc<Mutex<PartyState>>,
+    pub rate_limiter: RateLimiter,
+    /// caps all players behind one address together, player ids are chosen by the client
+    pub address_limiter: RateLimiter
 }
 
 pub type ActixData = Data<SharedData>;
@@ -23,16 +45,99 @@ impl SharedData {
         SharedData {
             store: Arc::new(Mutex::new(HashMap::new())),
             last_update: Arc::new(Mutex::new(Local::now())),
-            metrics: MetricsContext::new()
+            history: Arc::new(Mutex::new(History::default())),
+            suggestions: Arc::new(Mutex::new(vec![])),
+            unknown_processes: Arc::new(Mutex::new(vec![])),
+            metrics: MetricsContext::new(),
+            events: EventBus::new(),
+            party: Arc::new(Mutex::new(PartyState::default())),
+            rate_limiter: RateLimiter::new(*RATE_LIMIT_PER_MINUTE, *RATE_LIMIT_BURST),
+            address_limiter: RateLimiter::new(*RATE_LIMIT_ADDRESS_PER_MINUTE, *RATE_LIMIT_ADDRESS_BURST)
         }
     }
 }
 
-pub fn get_scope() -> Scope {
-    let mut scope = web::scope("/api/v1");
+pub fn authorize(auth: Option<BearerAuth>) -> Result<()> {
+    match auth {
+        Some(auth) if auth.token() == PASSWORD.as_str() => Ok(()),
+        _ => Err(ApiError::unauthorized().into())
+    }
+}
+
+pub fn authorize_admin(auth: Option<BearerAuth>) -> Result<()> {
+    let Some(admin_password) = ADMIN_PASSWORD.as_ref() else {
+        return Err(ApiError::forbidden("admin endpoints are disabled").into());
+    };
+
+    match auth {
+        Some(auth) if auth.token() == admin_password.as_str() => Ok(()),
+        _ => Err(ApiError::unauthorized().into())
+    }
+}
+
+/// read-only endpoints only require the password if `PUBLIC_READ` is disabled
+pub fn authorize_read(auth: Option<BearerAuth>) -> Result<()> {
+    if *PUBLIC_READ {
+        return Ok(());
+    }
+
+    authorize(auth)
+}
 
+fn json_error(err: JsonPayloadError, _req: &HttpRequest) -> actix_web::Error {
+    match err {
+        JsonPayloadError::Overflow { .. } | JsonPayloadError::OverflowKnownLength { .. } => ApiError::payload_too_large("body too large").with_details(err).into(),
+        err => ApiError::bad_request("invalid body").with_details(err).into()
+    }
+}
+
+fn query_error(err: QueryPayloadError, _req: &HttpRequest) -> actix_web::Error {
+    ApiError::bad_request("invalid query").with_details(err).into()
+}
+
+fn path_error(err: PathError, _req: &HttpRequest) -> actix_web::Error {
+    ApiError::bad_request("invalid path").with_details(err).into()
+}
+
+async fn not_found() -> Result<()> {
+    Err(ApiError::not_found().into())
+}
+
+pub fn get_scope() -> impl HttpServiceFactory {
+    web::scope("/api")
+        .app_data(web::JsonConfig::default().limit(*MAX_BODY_BYTES).error_handler(json_error))
+        .app_data(web::QueryConfig::default().error_handler(query_error))
+        .app_data(web::PathConfig::default().error_handler(path_error))
+        .wrap(from_fn(protocol::check_protocol))
+        .default_service(web::to(not_found))
+        .service(get_v1_scope())
+        .service(get_v2_scope())
+}
+
+fn get_v1_scope() -> Scope {
+    let mut scope = web::scope("/v1").default_service(web::to(not_found));
+
+    scope = protocol::get_services(scope);
     scope = now_playing::get_services(scope);
     scope = games::get_services(scope);
+    scope = history::get_services(scope);
+    scope = leaderboards::get_services(scope);
+    scope = players::get_services(scope);
+    scope = suggestions::get_services(scope);
+    scope = unknown_processes::get_services(scope);
+    scope = export::get_services(scope);
+    scope = recap::get_services(scope);
+    scope = timeline::get_services(scope);
+    scope = coplay::get_services(scope);
+
+    scope
+}
+
+/// endpoints move here once they change incompatibly, `/api/v1` keeps serving older clients
+fn get_v2_scope() -> Scope {
+    let mut scope = web::scope("/v2").default_service(web::to(not_found));
+
+    scope = protocol::get_services(scope);
 
     scope
 }
\ No newline at end of file
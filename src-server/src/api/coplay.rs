@@ -0,0 +1,43 @@
+use actix_web::{get, web::{self, Path}, HttpResponse, Responder, Result, Scope};
+use actix_web_httpauth::extractors::bearer::BearerAuth;
+use common::response::coplay::BuddiesResponse;
+use serde::Deserialize;
+
+use crate::{api::{authorize_read, history::parse_timestamp, ActixData}, repo::coplay::{get_buddies, get_graph}};
+
+#[derive(Deserialize)]
+struct CoPlayParams {
+    from: Option<String>,
+    to: Option<String>
+}
+
+#[derive(Deserialize)]
+struct BuddiesParameters {
+    player: String
+}
+
+#[get("/coplay")]
+async fn coplay(data: ActixData, auth: Option<BearerAuth>, query: web::Query<CoPlayParams>) -> Result<impl Responder> {
+    authorize_read(auth)?;
+
+    let query = query.into_inner();
+    let from = parse_timestamp("from", query.from)?;
+    let to = parse_timestamp("to", query.to)?;
+
+    Ok(HttpResponse::Ok().json(get_graph(&data, from, to).await))
+}
+
+#[get("/buddies/{player}")]
+async fn buddies(data: ActixData, auth: Option<BearerAuth>, path: Path<BuddiesParameters>) -> Result<impl Responder> {
+    authorize_read(auth)?;
+
+    Ok(HttpResponse::Ok().json(BuddiesResponse {
+        buddies: get_buddies(&data, &path.player).await
+    }))
+}
+
+pub fn get_services(scope: Scope) -> Scope {
+    scope
+        .service(coplay)
+        .service(buddies)
+}
\ No newline at end of file
@@ -0,0 +1,103 @@
+use std::{fmt::{self, Display}, time::Duration};
+
+use actix_web::{http::{header::RETRY_AFTER, StatusCode}, HttpResponse, ResponseError};
+use common::response::error::{ErrorCode, ErrorResponse};
+
+#[derive(Debug)]
+pub struct ApiError {
+    response: ErrorResponse,
+    /// sent as `Retry-After` in seconds
+    retry_after: Option<u64>
+}
+
+impl ApiError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> ApiError {
+        ApiError {
+            response: ErrorResponse {
+                code,
+                message: message.into(),
+                details: None
+            },
+            retry_after: None
+        }
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> ApiError {
+        ApiError::new(ErrorCode::BadRequest, message)
+    }
+
+    pub fn unauthorized() -> ApiError {
+        ApiError::new(ErrorCode::Unauthorized, "unauthorized")
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> ApiError {
+        ApiError::new(ErrorCode::Forbidden, message)
+    }
+
+    pub fn not_found() -> ApiError {
+        ApiError::new(ErrorCode::NotFound, "not found")
+    }
+
+    pub fn payload_too_large(message: impl Into<String>) -> ApiError {
+        ApiError::new(ErrorCode::PayloadTooLarge, message)
+    }
+
+    pub fn unprocessable(message: impl Into<String>) -> ApiError {
+        ApiError::new(ErrorCode::UnprocessableEntity, message)
+    }
+
+    pub fn too_many_requests(retry_after: Duration) -> ApiError {
+        let mut error = ApiError::new(ErrorCode::TooManyRequests, "too many requests");
+
+        // round up, retrying a moment too early would be rejected again
+        error.retry_after = Some(retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0));
+        error
+    }
+
+    pub fn internal(message: impl Into<String>) -> ApiError {
+        ApiError::new(ErrorCode::Internal, message)
+    }
+
+    pub fn with_details(mut self, details: impl Display) -> ApiError {
+        self.response.details = Some(details.to_string());
+        self
+    }
+}
+
+impl Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.response.message)?;
+
+        if let Some(details) = &self.response.details {
+            write!(f, ": {details}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        match self.response.code {
+            ErrorCode::BadRequest => StatusCode::BAD_REQUEST,
+            ErrorCode::Unauthorized => StatusCode::UNAUTHORIZED,
+            ErrorCode::Forbidden => StatusCode::FORBIDDEN,
+            ErrorCode::NotFound => StatusCode::NOT_FOUND,
+            ErrorCode::UpgradeRequired => StatusCode::UPGRADE_REQUIRED,
+            ErrorCode::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            ErrorCode::UnprocessableEntity => StatusCode::UNPROCESSABLE_ENTITY,
+            ErrorCode::TooManyRequests => StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::Internal | ErrorCode::Unknown => StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let mut res = HttpResponse::build(self.status_code());
+
+        if let Some(retry_after) = self.retry_after {
+            res.insert_header((RETRY_AFTER, retry_after));
+        }
+
+        res.json(&self.response)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,61 @@
+use actix_web::{get, http::header::{ContentDisposition, DispositionParam, DispositionType}, web, HttpResponse, Responder, Result, Scope};
+use actix_web_httpauth::extractors::bearer::BearerAuth;
+use common::response::export::ExportTable;
+use serde::Deserialize;
+
+use crate::{api::{authorize, history::parse_timestamp, ActixData}, repo::export::{get_export, to_csv}};
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+enum ExportFormat {
+    #[default]
+    Json,
+    Csv
+}
+
+#[derive(Deserialize)]
+struct ExportParams {
+    from: Option<String>,
+    to: Option<String>,
+    #[serde(default)]
+    format: ExportFormat,
+    /// csv only, json always contains every table
+    table: Option<ExportTable>
+}
+
+fn attachment(filename: String) -> ContentDisposition {
+    ContentDisposition {
+        disposition: DispositionType::Attachment,
+        parameters: vec![DispositionParam::Filename(filename)]
+    }
+}
+
+/// the full record of the party, unlike `/history` this always requires the password
+#[get("/export")]
+async fn export(data: ActixData, auth: Option<BearerAuth>, query: web::Query<ExportParams>) -> Result<impl Responder> {
+    authorize(auth)?;
+
+    let query = query.into_inner();
+    let from = parse_timestamp("from", query.from)?;
+    let to = parse_timestamp("to", query.to)?;
+    let export = get_export(&data, from, to).await;
+
+    Ok(match query.format {
+        ExportFormat::Json => HttpResponse::Ok()
+            .insert_header(attachment("export.json".to_string()))
+            .json(export),
+        ExportFormat::Csv => {
+            let table = query.table.unwrap_or(ExportTable::Sessions);
+
+            HttpResponse::Ok()
+                .content_type("text/csv; charset=utf-8")
+                .insert_header(attachment(format!("{}.csv", table.name())))
+                .body(to_csv(&export, table))
+        }
+    })
+}
+
+pub fn get_services(scope: Scope) -> Scope {
+    scope
+        .service(export)
+}
\ No newline at end of file
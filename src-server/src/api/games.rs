@@ -1,29 +1,85 @@
 use actix_files::NamedFile;
-use actix_web::{error::ErrorNotFound, get, web::Path, HttpResponse, Responder, Result, Scope};
+use actix_web::{get, http::header::{self, ContentType, HttpDate}, web::{self, Path, Query}, Either, HttpRequest, HttpResponse, Responder, Result, Scope};
 use common::{response::games::GamesResponse};
 use serde::Deserialize;
 
-use crate::{config::ICONS_DIR, repo::games::{get_game, get_games}};
+use crate::{api::error::ApiError, config::ICONS_DIR, repo::{games::{get_catalogue, get_game}, icons::{self, SIZES}}};
 
 #[derive(Deserialize)]
 struct GameIconParameters {
     name: String
 }
 
+#[derive(Deserialize)]
+struct GameIconQuery {
+    /// edge length of a normalized PNG, the original file is served if omitted
+    size: Option<u32>
+}
+
+fn is_fresh(req: &HttpRequest, etag: &str, modified: HttpDate) -> bool {
+    if let Some(if_none_match) = req.headers().get(header::IF_NONE_MATCH) {
+        return if_none_match.to_str()
+            .is_ok_and(|v| v == "*" || v.split(',').any(|t| t.trim() == etag));
+    }
+
+    req.headers().get(header::IF_MODIFIED_SINCE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<HttpDate>().ok())
+        .is_some_and(|since| modified <= since)
+}
+
 #[get("/games")]
-async fn games() -> Result<impl Responder> {    
-    Ok(HttpResponse::Ok().json(GamesResponse {
-        games: get_games().to_vec()
-    }))
+async fn games(req: HttpRequest) -> Result<impl Responder> {
+    let (games, etag, modified) = get_catalogue(|c| (c.games.clone(), c.etag.clone(), HttpDate::from(c.modified)));
+
+    if is_fresh(&req, &etag, modified) {
+        return Ok(HttpResponse::NotModified()
+            .insert_header((header::ETAG, etag))
+            .finish());
+    }
+
+    Ok(HttpResponse::Ok()
+        .insert_header((header::ETAG, etag))
+        .insert_header((header::LAST_MODIFIED, modified))
+        .json(GamesResponse {
+            games
+        }))
 }
 
+/// `NamedFile` answers conditional requests with `ETag` and `Last-Modified` on its own
 #[get("/games/{name}/icon")]
-async fn game_icon(path: Path<GameIconParameters>) -> Result<impl Responder> {
-    let Some(game) = get_game(&path.name) else {
-        return Err(ErrorNotFound("not found"));
+async fn game_icon(req: HttpRequest, path: Path<GameIconParameters>, query: Query<GameIconQuery>) -> Result<impl Responder> {
+    let Some(game) = get_game(&path.name).filter(|game| !game.icon.is_empty()) else {
+        return Err(ApiError::not_found().into());
+    };
+
+    let Some(size) = query.size else {
+        return NamedFile::open(format!("{}/{}", ICONS_DIR.as_str(), game.icon))
+            .map(Either::Left)
+            .map_err(|e| ApiError::not_found().with_details(e).into());
     };
 
-    Ok(NamedFile::open(format!("{}/{}", ICONS_DIR.as_str(), game.icon)))
+    if !SIZES.contains(&size) {
+        return Err(ApiError::bad_request(format!("size must be one of {SIZES:?}")).into());
+    }
+
+    // decoding and resizing would stall every other request of this worker
+    let icon = web::block(move || icons::get_icon(&game.icon, size)).await
+        .map_err(|e| ApiError::internal("could not convert icon").with_details(e))?
+        .map_err(|e| ApiError::internal("could not convert icon").with_details(e))?;
+    let modified = HttpDate::from(icon.modified);
+
+    if is_fresh(&req, &icon.etag, modified) {
+        return Ok(Either::Right(HttpResponse::NotModified()
+            .insert_header((header::ETAG, icon.etag))
+            .finish()));
+    }
+
+    Ok(Either::Right(HttpResponse::Ok()
+        .content_type(ContentType::png())
+        .insert_header((header::ETAG, icon.etag))
+        .insert_header((header::LAST_MODIFIED, modified))
+        .body(icon.bytes)))
 }
 
 pub fn get_services(scope: Scope) -> Scope {
@@ -0,0 +1,41 @@
+use actix_web::{get, web, HttpResponse, Responder, Result, Scope};
+use actix_web_httpauth::extractors::bearer::BearerAuth;
+use chrono::{DateTime, FixedOffset};
+use common::response::history::HistoryResponse;
+use serde::Deserialize;
+
+use crate::{api::{authorize_read, error::ApiError, ActixData}, repo::history::get_sessions};
+
+#[derive(Deserialize)]
+struct HistoryParams {
+    from: Option<String>,
+    to: Option<String>
+}
+
+pub(super) fn parse_timestamp(name: &str, value: Option<String>) -> Result<Option<DateTime<FixedOffset>>> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+
+    DateTime::parse_from_rfc3339(&value)
+        .map(Some)
+        .map_err(|_| ApiError::bad_request(format!("{name}: bad format")).into())
+}
+
+#[get("/history")]
+async fn history(data: ActixData, auth: Option<BearerAuth>, query: web::Query<HistoryParams>) -> Result<impl Responder> {
+    authorize_read(auth)?;
+
+    let query = query.into_inner();
+    let from = parse_timestamp("from", query.from)?;
+    let to = parse_timestamp("to", query.to)?;
+
+    Ok(HttpResponse::Ok().json(HistoryResponse {
+        sessions: get_sessions(&data, from, to).await
+    }))
+}
+
+pub fn get_services(scope: Scope) -> Scope {
+    scope
+        .service(history)
+}
\ No newline at end of file
@@ -0,0 +1,35 @@
+use actix_web::{get, web::Path, HttpResponse, Responder, Result, Scope};
+use actix_web_httpauth::extractors::bearer::BearerAuth;
+use common::response::leaderboards::{AchievementsResponse, LeaderboardsResponse};
+use serde::Deserialize;
+
+use crate::{api::{authorize_read, ActixData}, repo::leaderboards::{get_achievements, get_leaderboards}};
+
+#[derive(Deserialize)]
+struct AchievementsParameters {
+    player: String
+}
+
+#[get("/leaderboards")]
+async fn leaderboards(data: ActixData, auth: Option<BearerAuth>) -> Result<impl Responder> {
+    authorize_read(auth)?;
+
+    Ok(HttpResponse::Ok().json(LeaderboardsResponse {
+        leaderboards: get_leaderboards(&data).await
+    }))
+}
+
+#[get("/achievements/{player}")]
+async fn achievements(data: ActixData, auth: Option<BearerAuth>, path: Path<AchievementsParameters>) -> Result<impl Responder> {
+    authorize_read(auth)?;
+
+    Ok(HttpResponse::Ok().json(AchievementsResponse {
+        achievements: get_achievements(&data, &path.player).await
+    }))
+}
+
+pub fn get_services(scope: Scope) -> Scope {
+    scope
+        .service(leaderboards)
+        .service(achievements)
+}
\ No newline at end of file
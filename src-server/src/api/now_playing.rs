@@ -1,8 +1,8 @@
-use actix_web::{error, put, web, HttpResponse, Responder, Result, Scope};
+use actix_web::{delete, get, put, web, HttpRequest, HttpResponse, Responder, Result, Scope};
 use actix_web_httpauth::extractors::bearer::BearerAuth;
 use chrono::{DateTime};
-use common::{response::now_playing::NowPlayingEntry};
-use crate::{api::ActixData, config::PASSWORD, repo::now_playing::{get_list, update}};
+use common::{protocol::CLIENT_VERSION_HEADER, response::now_playing::NowPlayingEntry};
+use crate::{api::{authorize, authorize_read, error::ApiError, ActixData}, config::TRUST_PROXY, repo::now_playing::{get_list, remove, sanitize, update}};
 use serde::Deserialize;
 
 #[derive(Deserialize)]
@@ -11,15 +11,36 @@ struct NowPlayingParams {
 }
 
 #[put("/now-playing")]
-async fn put_now_playing(data: ActixData, auth: BearerAuth, body: web::Json<NowPlayingEntry>, query: web::Query<NowPlayingParams>) -> Result<impl Responder> {
-    if auth.token() != PASSWORD.as_str() {
-        return Err(error::ErrorUnauthorized("unauthorized"));
-    }
+async fn put_now_playing(data: ActixData, req: HttpRequest, auth: Option<BearerAuth>, body: web::Json<NowPlayingEntry>, query: web::Query<NowPlayingParams>) -> Result<impl Responder> {
+    authorize(auth)?;
+
+    let mut now_playing = body.into_inner();
+    let address = if *TRUST_PROXY {
+        req.connection_info().realip_remote_addr().map(str::to_owned)
+    } else {
+        req.peer_addr().map(|addr| addr.ip().to_string())
+    };
+
+    let address = address.unwrap_or_default();
+
+    // the id is part of the bucket key, so it is bounded before
+    sanitize(&mut now_playing)
+        .map_err(ApiError::unprocessable)?;
+
+    // players of a LAN usually share one public address, so each player gets a bucket
+    // and the address as a whole a larger one, otherwise new ids would mean new bursts,
+    // a rejected player doesn't use up the address' token
+    data.address_limiter.available(&address).await
+        .map_err(ApiError::too_many_requests)?;
+    data.rate_limiter.check(format!("{address}/{}", now_playing.player.id)).await
+        .map_err(ApiError::too_many_requests)?;
+    data.address_limiter.check(address).await
+        .map_err(ApiError::too_many_requests)?;
 
     let last_update = match query.into_inner().last_update {
         Some(last_update_str) => {
             let Ok(last_update) = DateTime::parse_from_rfc3339(&last_update_str) else {
-                return Err(error::ErrorBadRequest("last_update: bad format"));
+                return Err(ApiError::bad_request("last_update: bad format").into());
             };
 
             Some(last_update)
@@ -27,9 +48,11 @@ async fn put_now_playing(data: ActixData, auth: BearerAuth, body: web::Json<NowP
         None => None
     };
 
-    let now_playing = body.into_inner();
+    let version = req.headers().get(CLIENT_VERSION_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_owned);
     
-    update(&data, now_playing).await;
+    update(&data, now_playing, version).await;
 
     if let Some(last_update) = last_update {
         let update_lock = data.last_update.lock().await;
@@ -42,7 +65,30 @@ async fn put_now_playing(data: ActixData, auth: BearerAuth, body: web::Json<NowP
     Ok(HttpResponse::Ok().json(get_list(data).await))
 }
 
+#[derive(Deserialize)]
+struct LeaveParams {
+    player: String
+}
+
+#[delete("/now-playing")]
+async fn delete_now_playing(data: ActixData, auth: Option<BearerAuth>, query: web::Query<LeaveParams>) -> Result<impl Responder> {
+    authorize(auth)?;
+
+    remove(&data, &query.player).await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[get("/now-playing")]
+async fn get_now_playing(data: ActixData, auth: Option<BearerAuth>) -> Result<impl Responder> {
+    authorize_read(auth)?;
+
+    Ok(HttpResponse::Ok().json(get_list(data).await))
+}
+
 pub fn get_services(scope: Scope) -> Scope {
     scope
         .service(put_now_playing)
+        .service(get_now_playing)
+        .service(delete_now_playing)
 }
\ No newline at end of file
@@ -0,0 +1,19 @@
+use actix_web::{get, HttpResponse, Responder, Result, Scope};
+use actix_web_httpauth::extractors::bearer::BearerAuth;
+use common::response::players::PlayersResponse;
+
+use crate::{api::{authorize_read, ActixData}, repo::now_playing::get_players};
+
+#[get("/players")]
+async fn players(data: ActixData, auth: Option<BearerAuth>) -> Result<impl Responder> {
+    authorize_read(auth)?;
+
+    Ok(HttpResponse::Ok().json(PlayersResponse {
+        players: get_players(&data).await
+    }))
+}
+
+pub fn get_services(scope: Scope) -> Scope {
+    scope
+        .service(players)
+}
\ No newline at end of file
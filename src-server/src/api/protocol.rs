@@ -0,0 +1,51 @@
+use actix_web::{body::MessageBody, dev::{ServiceRequest, ServiceResponse}, get, middleware::Next, HttpResponse, Responder, Result, Scope};
+use common::{protocol::{Capability, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION, PROTOCOL_VERSION_HEADER}, response::{error::ErrorCode, info::InfoResponse}};
+
+use crate::api::error::ApiError;
+
+const CAPABILITIES: &[Capability] = &[
+    Capability::History,
+    Capability::Leaderboards,
+    Capability::Achievements,
+    Capability::Players,
+    Capability::Leave,
+    Capability::Suggestions,
+    Capability::UnknownProcesses,
+    Capability::Export,
+    Capability::Timeline,
+    Capability::CoPlay
+];
+
+/// rejects clients speaking a protocol older than `MIN_PROTOCOL_VERSION`,
+/// clients without the header predate versioning and are treated as protocol 1
+pub async fn check_protocol(req: ServiceRequest, next: Next<impl MessageBody>) -> Result<ServiceResponse<impl MessageBody>> {
+    let protocol = match req.headers().get(PROTOCOL_VERSION_HEADER) {
+        Some(header) => header.to_str().ok()
+            .and_then(|v| v.parse::<u32>().ok())
+            .ok_or_else(|| ApiError::bad_request(format!("{PROTOCOL_VERSION_HEADER}: bad format")))?,
+        None => 1
+    };
+
+    if protocol < MIN_PROTOCOL_VERSION {
+        return Err(ApiError::new(ErrorCode::UpgradeRequired, "protocol version no longer supported")
+            .with_details(format!("client speaks {protocol}, minimum is {MIN_PROTOCOL_VERSION}"))
+            .into());
+    }
+
+    next.call(req).await
+}
+
+#[get("/info")]
+async fn info() -> Result<impl Responder> {
+    Ok(HttpResponse::Ok().json(InfoResponse {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        protocol: PROTOCOL_VERSION,
+        min_protocol: MIN_PROTOCOL_VERSION,
+        capabilities: CAPABILITIES.to_vec()
+    }))
+}
+
+pub fn get_services(scope: Scope) -> Scope {
+    scope
+        .service(info)
+}
\ No newline at end of file
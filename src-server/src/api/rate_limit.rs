@@ -0,0 +1,131 @@
+use std::{collections::HashMap, sync::Arc, time::{Duration, Instant}};
+
+use tokio::sync::Mutex;
+
+/// buckets are only pruned once there are more than this many
+const PRUNE_THRESHOLD: usize = 1024;
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant
+}
+
+/// token bucket per key, refilled with `per_minute` tokens up to `burst`
+#[derive(Clone)]
+pub struct RateLimiter {
+    per_minute: u32,
+    burst: u32,
+    buckets: Arc<Mutex<HashMap<String, Bucket>>>
+}
+
+impl RateLimiter {
+    /// `per_minute` of `0` lets everything through
+    pub fn new(per_minute: u32, burst: u32) -> RateLimiter {
+        RateLimiter {
+            per_minute,
+            burst,
+            buckets: Arc::new(Mutex::new(HashMap::new()))
+        }
+    }
+
+    /// takes a token for `key`, otherwise returns how long until the next one is available
+    pub async fn check(&self, key: String) -> Result<(), Duration> {
+        self.acquire(&key, Instant::now(), true).await
+    }
+
+    /// like `check`, but leaves the token, for checking several limiters before taking from any
+    pub async fn available(&self, key: &str) -> Result<(), Duration> {
+        self.acquire(key, Instant::now(), false).await
+    }
+
+    async fn acquire(&self, key: &str, now: Instant, take: bool) -> Result<(), Duration> {
+        if self.per_minute == 0 {
+            return Ok(());
+        }
+
+        let per_second = f64::from(self.per_minute) / 60.0;
+        let burst = f64::from(self.burst.max(1));
+        let mut buckets_lock = self.buckets.lock().await;
+
+        if buckets_lock.len() > PRUNE_THRESHOLD {
+            // a bucket refilled completely is the same as no bucket
+            buckets_lock.retain(|_, b| b.tokens + now.saturating_duration_since(b.updated).as_secs_f64() * per_second < burst);
+        }
+
+        let bucket = buckets_lock.entry(key.to_string()).or_insert(Bucket {
+            tokens: burst,
+            updated: now
+        });
+
+        bucket.tokens = (bucket.tokens + now.saturating_duration_since(bucket.updated).as_secs_f64() * per_second).min(burst);
+        bucket.updated = now;
+
+        if bucket.tokens < 1.0 {
+            return Err(Duration::from_secs_f64((1.0 - bucket.tokens) / per_second));
+        }
+
+        if take {
+            bucket.tokens -= 1.0;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[actix_web::test]
+    async fn burst_then_reject_until_refilled() {
+        let limiter = RateLimiter::new(60, 3);
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            assert!(limiter.acquire("a", start, true).await.is_ok());
+        }
+
+        let wait = limiter.acquire("a", start, true).await.unwrap_err();
+
+        assert_eq!(wait, Duration::from_secs(1));
+        // other keys have their own bucket
+        assert!(limiter.acquire("b", start, true).await.is_ok());
+        assert!(limiter.acquire("a", start + Duration::from_millis(500), true).await.is_err());
+        assert!(limiter.acquire("a", start + Duration::from_secs(1), true).await.is_ok());
+        assert!(limiter.acquire("a", start + Duration::from_secs(1), true).await.is_err());
+    }
+
+    #[actix_web::test]
+    async fn refill_is_capped_at_burst() {
+        let limiter = RateLimiter::new(60, 2);
+        let start = Instant::now();
+
+        assert!(limiter.acquire("a", start, true).await.is_ok());
+
+        let later = start + Duration::from_secs(60);
+
+        assert!(limiter.acquire("a", later, true).await.is_ok());
+        assert!(limiter.acquire("a", later, true).await.is_ok());
+        assert!(limiter.acquire("a", later, true).await.is_err());
+    }
+
+    #[actix_web::test]
+    async fn available_leaves_the_token() {
+        let limiter = RateLimiter::new(60, 1);
+        let start = Instant::now();
+
+        assert!(limiter.acquire("a", start, false).await.is_ok());
+        assert!(limiter.acquire("a", start, false).await.is_ok());
+        assert!(limiter.acquire("a", start, true).await.is_ok());
+        assert!(limiter.acquire("a", start, false).await.is_err());
+    }
+
+    #[actix_web::test]
+    async fn zero_per_minute_is_unlimited() {
+        let limiter = RateLimiter::new(0, 0);
+
+        for _ in 0..100 {
+            assert!(limiter.check("a".to_string()).await.is_ok());
+        }
+    }
+}
\ No newline at end of file
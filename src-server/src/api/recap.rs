@@ -0,0 +1,40 @@
+use actix_web::{get, web, HttpResponse, Responder, Result, Scope};
+use actix_web_httpauth::extractors::bearer::BearerAuth;
+use common::response::recap::RecapFormat;
+use serde::Deserialize;
+
+use crate::{api::{authorize_read, error::ApiError, history::parse_timestamp, ActixData}, repo::recap::{get_recap, to_html, to_markdown}};
+
+#[derive(Deserialize)]
+struct RecapParams {
+    from: Option<String>,
+    to: Option<String>,
+    #[serde(default)]
+    format: RecapFormat
+}
+
+/// a summary of the party meant to be shared after the event
+#[get("/recap")]
+async fn recap(data: ActixData, auth: Option<BearerAuth>, query: web::Query<RecapParams>) -> Result<impl Responder> {
+    authorize_read(auth)?;
+
+    let query = query.into_inner();
+    let from = parse_timestamp("from", query.from)?;
+    let to = parse_timestamp("to", query.to)?;
+    let recap = get_recap(&data, from, to).await
+        .map_err(ApiError::bad_request)?;
+
+    Ok(match query.format {
+        RecapFormat::Html => HttpResponse::Ok()
+            .content_type("text/html; charset=utf-8")
+            .body(to_html(&recap)),
+        RecapFormat::Markdown => HttpResponse::Ok()
+            .content_type("text/markdown; charset=utf-8")
+            .body(to_markdown(&recap))
+    })
+}
+
+pub fn get_services(scope: Scope) -> Scope {
+    scope
+        .service(recap)
+}
\ No newline at end of file
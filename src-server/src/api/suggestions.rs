@@ -0,0 +1,63 @@
+use actix_web::{delete, get, post, web::{self, Path}, HttpResponse, Responder, Result, Scope};
+use actix_web_httpauth::extractors::bearer::BearerAuth;
+use common::response::suggestions::{GameApproval, SuggestedGame, SuggestionsResponse};
+use serde::Deserialize;
+
+use crate::{api::{authorize, authorize_admin, error::ApiError, ActixData}, repo::{icons, suggestions::{approve, get_suggestions, remove, suggest}}};
+
+#[derive(Deserialize)]
+struct SuggestionParameters {
+    name: String
+}
+
+#[post("/games/suggestions")]
+async fn post_suggestion(data: ActixData, auth: Option<BearerAuth>, body: web::Json<SuggestedGame>) -> Result<impl Responder> {
+    authorize(auth)?;
+
+    suggest(&data, body.into_inner()).await
+        .map_err(ApiError::unprocessable)?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[get("/games/suggestions")]
+async fn suggestions(data: ActixData, auth: Option<BearerAuth>) -> Result<impl Responder> {
+    authorize_admin(auth)?;
+
+    Ok(HttpResponse::Ok().json(SuggestionsResponse {
+        suggestions: get_suggestions(&data).await
+    }))
+}
+
+#[post("/games/suggestions/{name}/approve")]
+async fn approve_suggestion(data: ActixData, auth: Option<BearerAuth>, path: Path<SuggestionParameters>, body: Option<web::Json<GameApproval>>) -> Result<impl Responder> {
+    authorize_admin(auth)?;
+
+    let (label, icon) = body.map(|b| (b.0.label, b.0.icon)).unwrap_or_default();
+    // a game without icon would serve the icons directory itself
+    let icon = icon.filter(|icon| icons::exists(icon))
+        .ok_or_else(|| ApiError::bad_request("icon: must name a file in the icons directory"))?;
+    let game = approve(&data, &path.name, label, icon).await
+        .map_err(|e| ApiError::internal("could not approve suggestion").with_details(e))?
+        .ok_or_else(ApiError::not_found)?;
+
+    Ok(HttpResponse::Ok().json(game))
+}
+
+#[delete("/games/suggestions/{name}")]
+async fn reject_suggestion(data: ActixData, auth: Option<BearerAuth>, path: Path<SuggestionParameters>) -> Result<impl Responder> {
+    authorize_admin(auth)?;
+
+    remove(&data, &path.name).await
+        .ok_or_else(ApiError::not_found)?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub fn get_services(scope: Scope) -> Scope {
+    scope
+        .service(post_suggestion)
+        .service(suggestions)
+        .service(approve_suggestion)
+        .service(reject_suggestion)
+}
\ No newline at end of file
@@ -0,0 +1,37 @@
+use actix_web::{get, web, HttpResponse, Responder, Result, Scope};
+use actix_web_httpauth::extractors::bearer::BearerAuth;
+use chrono::TimeDelta;
+use serde::Deserialize;
+
+use crate::{api::{authorize_read, error::ApiError, history::parse_timestamp, ActixData}, repo::timeline::{get_timeline, MAX_STEP, MIN_STEP}};
+
+#[derive(Deserialize)]
+struct TimelineParams {
+    from: Option<String>,
+    to: Option<String>,
+    /// bucket length in seconds
+    step: Option<i64>
+}
+
+#[get("/timeline")]
+async fn timeline(data: ActixData, auth: Option<BearerAuth>, query: web::Query<TimelineParams>) -> Result<impl Responder> {
+    authorize_read(auth)?;
+
+    let query = query.into_inner();
+    let from = parse_timestamp("from", query.from)?;
+    let to = parse_timestamp("to", query.to)?;
+    let step = query.step
+        .map(|step| TimeDelta::try_seconds(step).filter(|step| (MIN_STEP..=MAX_STEP).contains(step)))
+        .map(|step| step.ok_or_else(|| ApiError::bad_request(format!("step: between {} and {} seconds", MIN_STEP.num_seconds(), MAX_STEP.num_seconds()))))
+        .transpose()?;
+
+    let timeline = get_timeline(&data, from, to, step).await
+        .map_err(ApiError::bad_request)?;
+
+    Ok(HttpResponse::Ok().json(timeline))
+}
+
+pub fn get_services(scope: Scope) -> Scope {
+    scope
+        .service(timeline)
+}
\ No newline at end of file
@@ -0,0 +1,63 @@
+use actix_web::{delete, get, post, web::{self, Path}, HttpResponse, Responder, Result, Scope};
+use actix_web_httpauth::extractors::bearer::BearerAuth;
+use common::response::{suggestions::GameApproval, unknown_processes::{UnknownProcessesReport, UnknownProcessesResponse}};
+use serde::Deserialize;
+
+use crate::{api::{authorize, authorize_admin, error::ApiError, ActixData}, repo::{icons, unknown_processes::{get_unknown_processes, promote, remove, report}}};
+
+#[derive(Deserialize)]
+struct ProcessParameters {
+    name: String
+}
+
+#[post("/processes/unknown")]
+async fn post_report(data: ActixData, auth: Option<BearerAuth>, body: web::Json<UnknownProcessesReport>) -> Result<impl Responder> {
+    authorize(auth)?;
+
+    report(&data, body.into_inner()).await
+        .map_err(ApiError::unprocessable)?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[get("/processes/unknown")]
+async fn unknown_processes(data: ActixData, auth: Option<BearerAuth>) -> Result<impl Responder> {
+    authorize_admin(auth)?;
+
+    Ok(HttpResponse::Ok().json(UnknownProcessesResponse {
+        processes: get_unknown_processes(&data).await
+    }))
+}
+
+#[post("/processes/unknown/{name}/promote")]
+async fn promote_process(data: ActixData, auth: Option<BearerAuth>, path: Path<ProcessParameters>, body: Option<web::Json<GameApproval>>) -> Result<impl Responder> {
+    authorize_admin(auth)?;
+
+    let (label, icon) = body.map(|b| (b.0.label, b.0.icon)).unwrap_or_default();
+    // a game without icon would serve the icons directory itself
+    let icon = icon.filter(|icon| icons::exists(icon))
+        .ok_or_else(|| ApiError::bad_request("icon: must name a file in the icons directory"))?;
+    let game = promote(&data, &path.name, label, icon).await
+        .map_err(|e| ApiError::internal("could not promote process").with_details(e))?
+        .ok_or_else(ApiError::not_found)?;
+
+    Ok(HttpResponse::Ok().json(game))
+}
+
+#[delete("/processes/unknown/{name}")]
+async fn dismiss_process(data: ActixData, auth: Option<BearerAuth>, path: Path<ProcessParameters>) -> Result<impl Responder> {
+    authorize_admin(auth)?;
+
+    remove(&data, &path.name).await
+        .ok_or_else(ApiError::not_found)?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub fn get_services(scope: Scope) -> Scope {
+    scope
+        .service(post_report)
+        .service(unknown_processes)
+        .service(promote_process)
+        .service(dismiss_process)
+}
\ No newline at end of file
@@ -2,16 +2,26 @@ use std::{env, sync::LazyLock, fmt::Write as _};
 
 use sha2::{Sha256, Digest};
 
-pub static PASSWORD: LazyLock<String> = LazyLock::new(|| {
-    let password = env::var("PASSWORD")
-        .expect("environment variable PASSWORD not set");
+fn hash(password: String) -> String {
     let mut hasher = Sha256::new();
     hasher.update(password);
     let mut out = String::new();
     write!(out, "{:x}", hasher.finalize()).map_err(|e| e.to_string())
-        .expect("could not hash PASSWORD");
+        .expect("could not hash password");
 
     out
+}
+
+pub static PASSWORD: LazyLock<String> = LazyLock::new(|| {
+    let password = env::var("PASSWORD")
+        .expect("environment variable PASSWORD not set");
+
+    hash(password)
+});
+
+/// admin endpoints are disabled unless this is set
+pub static ADMIN_PASSWORD: LazyLock<Option<String>> = LazyLock::new(|| {
+    env::var("ADMIN_PASSWORD").ok().map(hash)
 });
 
 pub static GAMES_FILE: LazyLock<String> = LazyLock::new(|| {    
@@ -20,4 +30,104 @@ pub static GAMES_FILE: LazyLock<String> = LazyLock::new(|| {
 
 pub static ICONS_DIR: LazyLock<String> = LazyLock::new(|| {    
     env::var("ICONS_DIR").unwrap_or("icons".to_string())
+});
+
+pub static DASHBOARD_DIR: LazyLock<String> = LazyLock::new(|| {
+    env::var("DASHBOARD_DIR").unwrap_or("dashboard".to_string())
+});
+
+pub static PUBLIC_READ: LazyLock<bool> = LazyLock::new(|| {
+    env::var("PUBLIC_READ")
+        .map(|v| v != "false" && v != "0")
+        .unwrap_or(true)
+});
+
+pub static SNAPSHOT_FILE: LazyLock<String> = LazyLock::new(|| {
+    env::var("SNAPSHOT_FILE").unwrap_or("snapshot.json".to_string())
+});
+
+/// days finished sessions are kept, `0` keeps them forever
+pub static HISTORY_RETENTION_DAYS: LazyLock<u32> = LazyLock::new(|| {
+    env::var("HISTORY_RETENTION_DAYS").ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(30)
+});
+
+/// finished sessions kept at most, the oldest are dropped first
+pub static HISTORY_MAX_SESSIONS: LazyLock<usize> = LazyLock::new(|| {
+    env::var("HISTORY_MAX_SESSIONS").ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(100_000)
+});
+
+/// webhooks are disabled unless this is set
+pub static WEBHOOKS_FILE: LazyLock<Option<String>> = LazyLock::new(|| {
+    env::var("WEBHOOKS_FILE").ok()
+});
+
+/// e.g. `http://pushgateway:9091`, metrics are only pushed if this or `REMOTE_WRITE_URL` is set
+pub static PUSHGATEWAY_URL: LazyLock<Option<String>> = LazyLock::new(|| {
+    env::var("PUSHGATEWAY_URL").ok()
+});
+
+/// e.g. `http://prometheus:9090/api/v1/write`
+pub static REMOTE_WRITE_URL: LazyLock<Option<String>> = LazyLock::new(|| {
+    env::var("REMOTE_WRITE_URL").ok()
+});
+
+pub static PUSH_JOB: LazyLock<String> = LazyLock::new(|| {
+    env::var("PUSH_JOB").unwrap_or("lan-manager".to_string())
+});
+
+/// seconds between two pushes
+pub static PUSH_INTERVAL: LazyLock<u32> = LazyLock::new(|| {
+    let Ok(interval) = env::var("PUSH_INTERVAL") else {
+        return 15;
+    };
+
+    interval.parse().ok()
+        .filter(|interval| *interval > 0)
+        .expect("PUSH_INTERVAL must be a number of seconds greater than 0")
+});
+
+/// reports per minute and player, `0` disables rate limiting
+pub static RATE_LIMIT_PER_MINUTE: LazyLock<u32> = LazyLock::new(|| {
+    env::var("RATE_LIMIT_PER_MINUTE").ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(60)
+});
+
+/// reports a player may send at once before the rate limit applies
+pub static RATE_LIMIT_BURST: LazyLock<u32> = LazyLock::new(|| {
+    env::var("RATE_LIMIT_BURST").ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(10)
+});
+
+/// reports per minute from one address across all its players, `0` disables the limit
+pub static RATE_LIMIT_ADDRESS_PER_MINUTE: LazyLock<u32> = LazyLock::new(|| {
+    env::var("RATE_LIMIT_ADDRESS_PER_MINUTE").ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(300)
+});
+
+/// reports one address may send at once before its rate limit applies
+pub static RATE_LIMIT_ADDRESS_BURST: LazyLock<u32> = LazyLock::new(|| {
+    env::var("RATE_LIMIT_ADDRESS_BURST").ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(30)
+});
+
+/// largest accepted json body in bytes
+pub static MAX_BODY_BYTES: LazyLock<usize> = LazyLock::new(|| {
+    env::var("MAX_BODY_BYTES").ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(64 * 1024)
+});
+
+/// take the client address from `Forwarded`/`X-Forwarded-For`, only enable behind a reverse proxy
+pub static TRUST_PROXY: LazyLock<bool> = LazyLock::new(|| {
+    env::var("TRUST_PROXY")
+        .map(|v| v != "false" && v != "0")
+        .unwrap_or(false)
 });
\ No newline at end of file
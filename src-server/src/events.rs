@@ -0,0 +1,143 @@
+use common::{game::Game, response::now_playing::Player};
+use log::{debug, info, warn};
+use serde::Serialize;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::{api::SharedData, metrics, notifier, repo::games::get_game};
+
+/// events a slow subscriber may fall behind before it starts missing some
+const CAPACITY: usize = 256;
+
+#[derive(Serialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DomainEvent {
+    PlayerJoined { player: Player },
+    PlayerLeft { player: Player },
+    GameStarted { player: Player, game: Game },
+    GameStopped { player: Player, game: Game },
+    /// `game` is `None` once no game has a majority anymore
+    MajorityChanged { game: Option<Game>, players: usize, online: usize },
+    /// more players online at once than ever before since the server started
+    OnlineRecord { online: usize },
+    /// sent on every report, drives time based metrics
+    Heartbeat { player: Player, games: Vec<Game> }
+}
+
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<DomainEvent>
+}
+
+impl EventBus {
+    pub fn new() -> EventBus {
+        EventBus {
+            sender: broadcast::channel(CAPACITY).0
+        }
+    }
+
+    pub fn publish(&self, event: DomainEvent) {
+        // without subscribers there is nobody to tell
+        let _ = self.sender.send(event);
+    }
+
+    /// runs `handler` for every event published from now on
+    pub fn subscribe<F: Future<Output = ()> + Send + 'static>(&self, name: &'static str, handler: impl Fn(DomainEvent) -> F + Send + 'static) {
+        self.subscribe_with_resync(name, handler, || async {});
+    }
+
+    /// like `subscribe`, but runs `resync` after falling behind, for subscribers whose state must not drift
+    pub fn subscribe_with_resync<F, R>(&self, name: &'static str, handler: impl Fn(DomainEvent) -> F + Send + 'static, resync: impl Fn() -> R + Send + 'static)
+    where
+        F: Future<Output = ()> + Send + 'static,
+        R: Future<Output = ()> + Send + 'static
+    {
+        let mut receiver = self.sender.subscribe();
+
+        tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => handler(event).await,
+                    Err(RecvError::Lagged(missed)) => {
+                        warn!("{name} fell behind, missed {missed} event(s)");
+                        resync().await;
+                    },
+                    Err(RecvError::Closed) => return
+                }
+            }
+        });
+    }
+}
+
+async fn log(event: DomainEvent) {
+    match event {
+        DomainEvent::PlayerJoined { player } => info!("{} joined", player.name),
+        DomainEvent::PlayerLeft { player } => info!("{} left", player.name),
+        DomainEvent::GameStarted { player, game } => info!("{} started {}", player.name, game.label),
+        DomainEvent::GameStopped { player, game } => info!("{} stopped {}", player.name, game.label),
+        DomainEvent::MajorityChanged { game: Some(game), players, online } => info!("majority is playing {} ({players} of {online})", game.label),
+        DomainEvent::MajorityChanged { game: None, .. } => info!("majority dissolved"),
+        DomainEvent::OnlineRecord { online } => info!("new record of {online} player(s) online"),
+        DomainEvent::Heartbeat { player, .. } => debug!("heartbeat of {}", player.name)
+    }
+}
+
+pub fn spawn_subscribers(data: &SharedData) {
+    let metrics = data.metrics.clone();
+    let data_resync = data.clone();
+
+    data.events.subscribe("logging", log);
+    // missed heartbeats or leaves would leave `lan_game_active` stale, so it is rebuilt from the store
+    data.events.subscribe_with_resync("metrics", move |event| {
+        let metrics = metrics.clone();
+
+        async move {
+            metrics::handle_event(&metrics, event).await;
+        }
+    }, move || {
+        let data = data_resync.clone();
+
+        async move {
+            let playing: Vec<(Player, Vec<Game>)> = data.store.lock().await.values()
+                .map(|info| (info.entry.player.clone(), info.entry.games.iter().filter_map(|g| get_game(g)).collect()))
+                .collect();
+
+            metrics::resync(&data.metrics, playing).await;
+        }
+    });
+    data.events.subscribe("webhooks", notifier::handle_event);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{atomic::{AtomicUsize, Ordering}, Arc};
+
+    use super::*;
+
+    #[actix_web::test]
+    async fn lagging_subscriber_resyncs() {
+        let bus = EventBus::new();
+        let handled = Arc::new(AtomicUsize::new(0));
+        let resynced = Arc::new(AtomicUsize::new(0));
+        let (handled_clone, resynced_clone) = (handled.clone(), resynced.clone());
+
+        bus.subscribe_with_resync("test", move |_| {
+            handled_clone.fetch_add(1, Ordering::SeqCst);
+
+            async {}
+        }, move || {
+            resynced_clone.fetch_add(1, Ordering::SeqCst);
+
+            async {}
+        });
+
+        // the subscriber only runs once this task yields, by then the channel has overflowed
+        for online in 0..CAPACITY + 10 {
+            bus.publish(DomainEvent::OnlineRecord { online });
+        }
+
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        assert_eq!(resynced.load(Ordering::SeqCst), 1);
+        assert_eq!(handled.load(Ordering::SeqCst), CAPACITY);
+    }
+}
\ No newline at end of file
@@ -1,21 +1,58 @@
 use std::time::Duration;
 
+use actix_files::Files;
 use actix_web::{App, HttpServer, web::Data};
 use clokwerk::{AsyncScheduler, TimeUnits};
+use env_logger::Env;
+use log::{error, info};
 
-use crate::{api::SharedData, repo::now_playing};
+use crate::{api::SharedData, config::{DASHBOARD_DIR, PUSH_INTERVAL}, repo::{games, now_playing}};
 
 mod api;
 mod repo;
 mod config;
 mod metrics;
+mod snapshot;
+mod notifier;
+mod events;
+mod push;
+
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let Ok(mut terminate) = signal(SignalKind::terminate()) else {
+            error!("could not listen for SIGTERM");
+            return;
+        };
+
+        tokio::select! {
+            _ = terminate.recv() => {},
+            _ = tokio::signal::ctrl_c() => {}
+        }
+    }
+
+    #[cfg(not(unix))]
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        error!("could not listen for ctrl-c: {e}");
+    }
+}
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
+    env_logger::init_from_env(Env::default().default_filter_or("info"));
+
     let mut scheduler = AsyncScheduler::new();
     let shared = SharedData::new();
     let shared_clone = shared.clone();
 
+    events::spawn_subscribers(&shared);
+
+    if let Err(e) = snapshot::restore(&shared).await {
+        error!("failed to restore snapshot: {e}");
+    }
+
     scheduler.every(5.seconds()).run(move || {
         let shared_clone_inner = shared_clone.clone();
 
@@ -24,6 +61,22 @@ async fn main() -> std::io::Result<()> {
         }
     });
 
+    scheduler.every(30.seconds()).run(|| async {
+        games::reload();
+    });
+
+    if push::is_enabled() {
+        let shared_push = shared.clone();
+
+        scheduler.every(PUSH_INTERVAL.seconds()).run(move || {
+            let shared_push_inner = shared_push.clone();
+
+            async move {
+                push::push(&shared_push_inner.metrics).await;
+            }
+        });
+    }
+
     tokio::spawn(async move {
         loop {
             scheduler.run_pending().await;
@@ -31,15 +84,32 @@ async fn main() -> std::io::Result<()> {
         }
     });
 
-    HttpServer::new(move || {
-        let shared = shared.clone();
+    let shared_server = shared.clone();
+    let server = HttpServer::new(move || {
+        let shared = shared_server.clone();
 
         App::new()
             .app_data(Data::new(shared))
             .service(api::get_scope())
             .service(metrics::get_scope())
+            .service(Files::new("/", DASHBOARD_DIR.as_str()).index_file("index.html"))
     })
+    .disable_signals()
     .bind(("0.0.0.0", 80))?
-    .run()
-    .await
+    .run();
+    let handle = server.handle();
+
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        info!("shutting down");
+        handle.stop(true).await;
+    });
+
+    server.await?;
+
+    if let Err(e) = snapshot::save(&shared).await {
+        error!("failed to save snapshot: {e}");
+    }
+
+    Ok(())
 }
\ No newline at end of file
@@ -1,12 +1,13 @@
 use std::{collections::{HashMap, HashSet}, sync::Arc};
 
-use actix_web::{error::ErrorInternalServerError, web, HttpResponse, Responder, Result, Scope};
+use actix_web::{web, HttpResponse, Responder, Result, Scope};
 use chrono::{DateTime, Duration, Local, TimeDelta};
 use common::{game::Game, response::now_playing::Player};
-use prometheus::{Counter, CounterVec, Gauge, GaugeVec, Opts, Registry, TextEncoder};
+use prometheus::{proto::MetricFamily, Counter, CounterVec, Gauge, GaugeVec, Opts, Registry, TextEncoder};
+use serde::{Deserialize, Serialize};
 use tokio::sync::Mutex;
 
-use crate::api::ActixData;
+use crate::{api::{error::ApiError, ActixData}, events::DomainEvent};
 
 struct MetricsFamily {
     lan_game_seconds_total: Counter,
@@ -27,6 +28,13 @@ struct PlayerMetricsContext {
     map: HashMap<Game, MetricsFamily>
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct CounterSnapshot {
+    player: Player,
+    game: Game,
+    seconds: f64
+}
+
 #[derive(Clone)]
 pub struct MetricsContext {
     registry: Registry,
@@ -55,22 +63,26 @@ impl MetricsContext {
             map: Arc::new(Mutex::new(HashMap::new()))
         }
     }
+
+    pub fn gather(&self) -> Vec<MetricFamily> {
+        self.registry.gather()
+    }
 }
 
 pub async fn scrape(data: ActixData) -> Result<impl Responder> {
     let mut buffer = String::new();
     let encoder = TextEncoder::new();
-    let metric_families = data.metrics.registry.gather();
+    let metric_families = data.metrics.gather();
 
     encoder.encode_utf8(&metric_families, &mut buffer)
-        .map_err(|e| ErrorInternalServerError(format!("failed to encode metrics: {e}")))?;
+        .map_err(|e| ApiError::internal("failed to encode metrics").with_details(e))?;
 
     Ok(HttpResponse::Ok()
         .content_type("text/plain")
         .body(buffer))
 }
 
-pub async fn record_played_games(metrics: &MetricsContext, player: Player, games: Vec<Game>) {
+async fn record_played_games(metrics: &MetricsContext, player: Player, games: Vec<Game>) {
     let mut counter_lock = metrics.map.lock().await;
     let by_player = counter_lock
         .entry(player.clone())
@@ -107,7 +119,7 @@ pub async fn record_played_games(metrics: &MetricsContext, player: Player, games
     }
 }
 
-pub async fn record_expired_player(metrics: &MetricsContext, player: &String) {
+async fn record_expired_player(metrics: &MetricsContext, player: &String) {
     let counter_lock = metrics.map.lock().await;
     let Some((_, by_player)) = counter_lock.iter().find(|(p, _)| &p.id == player) else {
         return;
@@ -118,7 +130,119 @@ pub async fn record_expired_player(metrics: &MetricsContext, player: &String) {
     }
 }
 
+pub async fn handle_event(metrics: &MetricsContext, event: DomainEvent) {
+    match event {
+        DomainEvent::Heartbeat { player, games } => record_played_games(metrics, player, games).await,
+        DomainEvent::PlayerLeft { player } => record_expired_player(metrics, &player.id).await,
+        _ => {}
+    }
+}
+
+/// sets `lan_game_active` to exactly the games in `playing`, after events were missed
+pub async fn resync(metrics: &MetricsContext, playing: impl IntoIterator<Item = (Player, Vec<Game>)>) {
+    let mut counter_lock = metrics.map.lock().await;
+
+    for by_game in counter_lock.values().flat_map(|by_player| by_player.map.values()) {
+        by_game.lan_game_active.set(0.0);
+    }
+
+    for (player, games) in playing {
+        let by_player = counter_lock
+            .entry(player.clone())
+            .or_insert(PlayerMetricsContext {
+                last_seen: Local::now() - Duration::seconds(5),
+                map: HashMap::new()
+            });
+
+        for game in games {
+            by_player.map
+                .entry(game.clone())
+                .or_insert(MetricsFamily::with(metrics, &player, &game))
+                .lan_game_active.set(1.0);
+        }
+    }
+}
+
+pub async fn snapshot_counters(metrics: &MetricsContext) -> Vec<CounterSnapshot> {
+    let counter_lock = metrics.map.lock().await;
+
+    counter_lock.iter()
+        .flat_map(|(player, by_player)| by_player.map.iter()
+            .map(|(game, by_game)| CounterSnapshot {
+                player: player.clone(),
+                game: game.clone(),
+                seconds: by_game.lan_game_seconds_total.get()
+            })
+        )
+        .collect()
+}
+
+pub async fn restore_counters(metrics: &MetricsContext, counters: Vec<CounterSnapshot>) {
+    let mut counter_lock = metrics.map.lock().await;
+
+    for counter in counters {
+        let by_player = counter_lock
+            .entry(counter.player.clone())
+            .or_insert(PlayerMetricsContext {
+                last_seen: Local::now() - Duration::seconds(5),
+                map: HashMap::new()
+            });
+        let by_game = by_player.map
+            .entry(counter.game.clone())
+            .or_insert(MetricsFamily::with(metrics, &counter.player, &counter.game));
+
+        by_game.lan_game_seconds_total.inc_by(counter.seconds);
+    }
+}
+
 pub fn get_scope() -> Scope {
     web::scope("/metrics")
         .route("", web::get().to(scrape))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(id: &str) -> Player {
+        Player {
+            id: id.to_string(),
+            name: id.to_string()
+        }
+    }
+
+    fn game(name: &str) -> Game {
+        Game {
+            name: name.to_string(),
+            label: name.to_string(),
+            icon: String::new()
+        }
+    }
+
+    fn active(metrics: &MetricsContext, player: &Player, game: &Game) -> f64 {
+        metrics.lan_game_active_vec.with_label_values(&[&player.id, &game.name, &player.name, &game.label, &game.icon]).get()
+    }
+
+    #[actix_web::test]
+    async fn resync_clears_games_nobody_plays_anymore() {
+        let metrics = MetricsContext::new();
+
+        record_played_games(&metrics, player("1"), vec![game("a"), game("b")]).await;
+        record_played_games(&metrics, player("2"), vec![game("a")]).await;
+        resync(&metrics, vec![(player("1"), vec![game("b")])]).await;
+
+        assert_eq!(active(&metrics, &player("1"), &game("a")), 0.0);
+        assert_eq!(active(&metrics, &player("1"), &game("b")), 1.0);
+        assert_eq!(active(&metrics, &player("2"), &game("a")), 0.0);
+    }
+
+    #[actix_web::test]
+    async fn resync_adds_games_whose_heartbeat_was_missed() {
+        let metrics = MetricsContext::new();
+
+        resync(&metrics, vec![(player("1"), vec![game("a")])]).await;
+
+        assert_eq!(active(&metrics, &player("1"), &game("a")), 1.0);
+        assert_eq!(metrics.lan_game_seconds_total_vec.with_label_values(&["1", "a", "1", "a", ""]).get(), 0.0);
+    }
 }
\ No newline at end of file
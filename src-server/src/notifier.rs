@@ -0,0 +1,439 @@
+use std::{collections::{HashMap, VecDeque}, fs, sync::LazyLock, time::{Duration, Instant}};
+
+use log::{info, warn};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::sync::Mutex;
+
+use crate::{config::WEBHOOKS_FILE, events::DomainEvent};
+
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    PlayerJoined,
+    PlayerLeft,
+    GameStarted,
+    GameStopped,
+    MajorityChanged,
+    OnlineRecord,
+    Heartbeat
+}
+
+fn kind(event: &DomainEvent) -> EventKind {
+    match event {
+        DomainEvent::PlayerJoined { .. } => EventKind::PlayerJoined,
+        DomainEvent::PlayerLeft { .. } => EventKind::PlayerLeft,
+        DomainEvent::GameStarted { .. } => EventKind::GameStarted,
+        DomainEvent::GameStopped { .. } => EventKind::GameStopped,
+        DomainEvent::MajorityChanged { .. } => EventKind::MajorityChanged,
+        DomainEvent::OnlineRecord { .. } => EventKind::OnlineRecord,
+        DomainEvent::Heartbeat { .. } => EventKind::Heartbeat
+    }
+}
+
+fn default_template(kind: EventKind) -> &'static str {
+    match kind {
+        EventKind::PlayerJoined => "{player} ist der LAN beigetreten",
+        EventKind::PlayerLeft => "{player} hat die LAN verlassen",
+        EventKind::GameStarted => "{player} spielt {game}",
+        EventKind::GameStopped => "{player} spielt nicht mehr {game}",
+        EventKind::MajorityChanged => "Die Mehrheit spielt jetzt {game} ({players} von {online})",
+        EventKind::OnlineRecord => "Neuer Rekord: {online} Spieler gleichzeitig online",
+        EventKind::Heartbeat => "{player} ist online"
+    }
+}
+
+/// replaces `{player}`, `{game}`, `{players}` and `{online}` in `template`, `None` if there is nothing to tell
+fn render(event: &DomainEvent, template: &str) -> Option<String> {
+    let text = match event {
+        DomainEvent::PlayerJoined { player } | DomainEvent::PlayerLeft { player } => template.replace("{player}", &player.name),
+        DomainEvent::GameStarted { player, game } | DomainEvent::GameStopped { player, game } => template
+            .replace("{player}", &player.name)
+            .replace("{game}", &game.label),
+        DomainEvent::MajorityChanged { game: Some(game), players, online } => template
+            .replace("{game}", &game.label)
+            .replace("{players}", &players.to_string())
+            .replace("{online}", &online.to_string()),
+        // a dissolved majority is not worth a chat message
+        DomainEvent::MajorityChanged { game: None, .. } => return None,
+        DomainEvent::OnlineRecord { online } => template.replace("{online}", &online.to_string()),
+        DomainEvent::Heartbeat { player, games } => template
+            .replace("{player}", &player.name)
+            .replace("{game}", &games.iter().map(|g| g.label.as_str()).collect::<Vec<&str>>().join(", "))
+    };
+
+    Some(text)
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum Target {
+    Discord { url: String },
+    /// `homeserver` is the base url, e.g. `https://matrix.org`
+    Matrix { homeserver: String, room: String, token: String },
+    /// receives every event as json, e.g. `{"type": "game_started", "player": {...}, "game": {...}}`
+    Generic { url: String }
+}
+
+fn default_max_per_minute() -> usize {
+    10
+}
+
+#[derive(Deserialize)]
+struct Webhook {
+    #[serde(flatten)]
+    target: Target,
+    /// the defaults of the target if empty
+    #[serde(default)]
+    events: Vec<EventKind>,
+    #[serde(default)]
+    templates: HashMap<EventKind, String>,
+    #[serde(default = "default_max_per_minute")]
+    max_per_minute: usize
+}
+
+impl Webhook {
+    fn wants(&self, kind: EventKind) -> bool {
+        if !self.events.is_empty() {
+            return self.events.contains(&kind);
+        }
+
+        match self.target {
+            Target::Generic { .. } => kind != EventKind::Heartbeat,
+            Target::Discord { .. } | Target::Matrix { .. } => matches!(kind, EventKind::PlayerJoined | EventKind::MajorityChanged | EventKind::OnlineRecord)
+        }
+    }
+
+    fn body(&self, event: &DomainEvent, kind: EventKind) -> Option<Value> {
+        let text = || render(event, self.templates.get(&kind).map_or(default_template(kind), String::as_str));
+
+        match self.target {
+            // names are chosen by players, so `@everyone` or role mentions must not ping anyone
+            Target::Discord { .. } => text().map(|text| json!({ "content": text, "allowed_mentions": { "parse": [] } })),
+            Target::Matrix { .. } => text().map(|text| json!({ "msgtype": "m.text", "body": text })),
+            Target::Generic { .. } => serde_json::to_value(event).ok()
+        }
+    }
+}
+
+struct Notifier {
+    webhooks: Vec<Webhook>,
+    /// send times within `RATE_WINDOW` per webhook
+    sent: Mutex<Vec<VecDeque<Instant>>>,
+    client: Client
+}
+
+fn read_webhooks() -> Vec<Webhook> {
+    let Some(path) = WEBHOOKS_FILE.as_ref() else {
+        return vec![];
+    };
+    let webhooks = fs::read_to_string(path)
+        .map_err(|e| format!("could not read {path}: {e}"))
+        .and_then(|buf| serde_json::from_str::<Vec<Webhook>>(&buf).map_err(|e| format!("could not parse {path}: {e}")));
+
+    match webhooks {
+        Ok(webhooks) => {
+            info!("loaded {} webhook(s)", webhooks.len());
+
+            webhooks
+        },
+        Err(e) => {
+            warn!("webhooks disabled: {e}");
+
+            vec![]
+        }
+    }
+}
+
+static NOTIFIER: LazyLock<Notifier> = LazyLock::new(|| {
+    let webhooks = read_webhooks();
+
+    Notifier {
+        sent: Mutex::new(webhooks.iter().map(|_| VecDeque::new()).collect()),
+        webhooks,
+        client: Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .expect("could not build webhook client")
+    }
+});
+
+async fn send(client: Client, target: &Target, body: Value) -> Result<(), String> {
+    let req = match target {
+        Target::Discord { url } | Target::Generic { url } => client.post(url),
+        Target::Matrix { homeserver, room, token } => {
+            // Matrix needs an id per message that is unique for the access token
+            let txn = format!("lan-manager-{}", chrono::Local::now().timestamp_nanos_opt().unwrap_or_default());
+
+            client.put(format!("{}/_matrix/client/v3/rooms/{room}/send/m.room.message/{txn}", homeserver.trim_end_matches('/')))
+                .bearer_auth(token)
+        }
+    };
+    let res = req.json(&body).send().await.map_err(|e| e.to_string())?;
+
+    if !res.status().is_success() {
+        return Err(format!("{}: {}", res.status(), res.text().await.unwrap_or_default()));
+    }
+
+    Ok(())
+}
+
+/// forgets send times older than `RATE_WINDOW` and records `now` if another message fits into the limit
+fn admit(sent: &mut VecDeque<Instant>, max_per_minute: usize, now: Instant) -> bool {
+    while sent.front().is_some_and(|t| now.duration_since(*t) > RATE_WINDOW) {
+        sent.pop_front();
+    }
+
+    if sent.len() >= max_per_minute {
+        return false;
+    }
+
+    sent.push_back(now);
+
+    true
+}
+
+/// posts `event` to every subscribed webhook that is below its rate limit
+pub async fn handle_event(event: DomainEvent) {
+    let notifier = &*NOTIFIER;
+    let kind = kind(&event);
+    let mut sent_lock = notifier.sent.lock().await;
+
+    for (webhook, sent) in notifier.webhooks.iter().zip(sent_lock.iter_mut()) {
+        if !webhook.wants(kind) {
+            continue;
+        }
+
+        let Some(body) = webhook.body(&event, kind) else {
+            continue;
+        };
+
+        if !admit(sent, webhook.max_per_minute, Instant::now()) {
+            warn!("webhook rate limit reached, dropping {kind:?}");
+            continue;
+        }
+
+        let client = notifier.client.clone();
+        let target = &webhook.target;
+
+        tokio::spawn(async move {
+            if let Err(e) = send(client, target, body).await {
+                warn!("failed to send webhook: {e}");
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer};
+    use common::{game::Game, response::now_playing::Player};
+
+    use super::*;
+
+    fn player() -> Player {
+        Player {
+            id: "1".to_string(),
+            name: "Alice".to_string()
+        }
+    }
+
+    fn game() -> Game {
+        Game {
+            name: "cs2.exe".to_string(),
+            label: "Counter-Strike 2".to_string(),
+            icon: "cs2.png".to_string()
+        }
+    }
+
+    fn webhook(config: Value) -> Webhook {
+        serde_json::from_value(config).unwrap()
+    }
+
+    struct Received {
+        method: String,
+        path: String,
+        authorization: Option<String>,
+        body: Value
+    }
+
+    /// local HTTP endpoint answering every request with `status`, returns its base url
+    fn mock(status: u16) -> (String, Arc<Mutex<Vec<Received>>>) {
+        let received = Arc::new(Mutex::new(vec![]));
+        let shared = received.clone();
+        let server = HttpServer::new(move || {
+            let received = shared.clone();
+
+            App::new().default_service(web::to(move |req: HttpRequest, body: web::Bytes| {
+                let received = received.clone();
+
+                async move {
+                    received.lock().await.push(Received {
+                        method: req.method().to_string(),
+                        path: req.path().to_string(),
+                        authorization: req.headers().get("authorization").and_then(|v| v.to_str().ok()).map(str::to_owned),
+                        body: serde_json::from_slice(&body).unwrap_or_default()
+                    });
+
+                    HttpResponse::build(actix_web::http::StatusCode::from_u16(status).unwrap()).body("nope")
+                }
+            }))
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+        let url = format!("http://{}", server.addrs()[0]);
+
+        actix_web::rt::spawn(server.run());
+
+        (url, received)
+    }
+
+    #[test]
+    fn render_replaces_placeholders() {
+        let started = DomainEvent::GameStarted { player: player(), game: game() };
+        let majority = DomainEvent::MajorityChanged { game: Some(game()), players: 3, online: 4 };
+        let heartbeat = DomainEvent::Heartbeat { player: player(), games: vec![game(), game()] };
+
+        assert_eq!(render(&started, "{player} -> {game}").as_deref(), Some("Alice -> Counter-Strike 2"));
+        assert_eq!(render(&majority, default_template(EventKind::MajorityChanged)).as_deref(), Some("Die Mehrheit spielt jetzt Counter-Strike 2 (3 von 4)"));
+        assert_eq!(render(&DomainEvent::OnlineRecord { online: 7 }, "{online}/{player}").as_deref(), Some("7/{player}"));
+        assert_eq!(render(&heartbeat, "{game}").as_deref(), Some("Counter-Strike 2, Counter-Strike 2"));
+    }
+
+    #[test]
+    fn render_skips_dissolved_majority() {
+        assert!(render(&DomainEvent::MajorityChanged { game: None, players: 0, online: 4 }, "{game}").is_none());
+    }
+
+    #[test]
+    fn wants_uses_target_defaults() {
+        let discord = webhook(json!({ "kind": "discord", "url": "http://localhost" }));
+        let generic = webhook(json!({ "kind": "generic", "url": "http://localhost" }));
+
+        assert!(discord.wants(EventKind::PlayerJoined));
+        assert!(discord.wants(EventKind::OnlineRecord));
+        assert!(!discord.wants(EventKind::GameStarted));
+        assert!(generic.wants(EventKind::GameStopped));
+        assert!(!generic.wants(EventKind::Heartbeat));
+    }
+
+    #[test]
+    fn wants_prefers_configured_events() {
+        let matrix = webhook(json!({
+            "kind": "matrix",
+            "homeserver": "http://localhost",
+            "room": "!room:localhost",
+            "token": "secret",
+            "events": ["game_started", "heartbeat"]
+        }));
+
+        assert!(matrix.wants(EventKind::GameStarted));
+        assert!(matrix.wants(EventKind::Heartbeat));
+        assert!(!matrix.wants(EventKind::PlayerJoined));
+    }
+
+    #[test]
+    fn body_uses_configured_template() {
+        let discord = webhook(json!({ "kind": "discord", "url": "http://localhost", "templates": { "player_joined": "Hallo {player}" } }));
+        let event = DomainEvent::PlayerJoined { player: player() };
+
+        assert_eq!(discord.body(&event, EventKind::PlayerJoined), Some(json!({ "content": "Hallo Alice", "allowed_mentions": { "parse": [] } })));
+    }
+
+    #[test]
+    fn discord_body_suppresses_mentions() {
+        let discord = webhook(json!({ "kind": "discord", "url": "http://localhost" }));
+        let event = DomainEvent::PlayerJoined {
+            player: Player {
+                id: "1".to_string(),
+                name: "@everyone".to_string()
+            }
+        };
+        let body = discord.body(&event, EventKind::PlayerJoined).unwrap();
+
+        assert!(body["content"].as_str().unwrap().contains("@everyone"));
+        assert_eq!(body["allowed_mentions"], json!({ "parse": [] }));
+    }
+
+    #[test]
+    fn admit_limits_per_minute() {
+        let start = Instant::now();
+        let mut sent = VecDeque::new();
+
+        assert!(admit(&mut sent, 2, start));
+        assert!(admit(&mut sent, 2, start + Duration::from_secs(1)));
+        assert!(!admit(&mut sent, 2, start + Duration::from_secs(2)));
+        // the first message left the window
+        assert!(admit(&mut sent, 2, start + RATE_WINDOW + Duration::from_millis(500)));
+        assert!(!admit(&mut sent, 2, start + RATE_WINDOW + Duration::from_millis(600)));
+        assert_eq!(sent.len(), 2);
+    }
+
+    #[actix_web::test]
+    async fn send_posts_discord_message() {
+        let (url, received) = mock(204);
+        let discord = webhook(json!({ "kind": "discord", "url": format!("{url}/api/webhooks/1/abc") }));
+        let event = DomainEvent::PlayerJoined { player: player() };
+
+        send(Client::new(), &discord.target, discord.body(&event, EventKind::PlayerJoined).unwrap()).await.unwrap();
+
+        let received = received.lock().await;
+
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].method, "POST");
+        assert_eq!(received[0].path, "/api/webhooks/1/abc");
+        assert_eq!(received[0].body, json!({ "content": "Alice ist der LAN beigetreten", "allowed_mentions": { "parse": [] } }));
+    }
+
+    #[actix_web::test]
+    async fn send_puts_matrix_message() {
+        let (url, received) = mock(200);
+        let matrix = webhook(json!({ "kind": "matrix", "homeserver": format!("{url}/"), "room": "!room:localhost", "token": "secret" }));
+        let event = DomainEvent::OnlineRecord { online: 5 };
+
+        send(Client::new(), &matrix.target, matrix.body(&event, EventKind::OnlineRecord).unwrap()).await.unwrap();
+        send(Client::new(), &matrix.target, matrix.body(&event, EventKind::OnlineRecord).unwrap()).await.unwrap();
+
+        let received = received.lock().await;
+        let prefix = "/_matrix/client/v3/rooms/!room:localhost/send/m.room.message/lan-manager-";
+
+        assert_eq!(received.len(), 2);
+        assert_eq!(received[0].method, "PUT");
+        assert!(received.iter().all(|r| r.path.starts_with(prefix)));
+        assert_ne!(received[0].path, received[1].path);
+        assert_eq!(received[0].authorization.as_deref(), Some("Bearer secret"));
+        assert_eq!(received[0].body, json!({ "msgtype": "m.text", "body": "Neuer Rekord: 5 Spieler gleichzeitig online" }));
+    }
+
+    #[actix_web::test]
+    async fn send_posts_generic_event() {
+        let (url, received) = mock(200);
+        let generic = webhook(json!({ "kind": "generic", "url": url }));
+        let event = DomainEvent::GameStarted { player: player(), game: game() };
+
+        send(Client::new(), &generic.target, generic.body(&event, EventKind::GameStarted).unwrap()).await.unwrap();
+
+        let received = received.lock().await;
+
+        assert_eq!(received[0].body["type"], "game_started");
+        assert_eq!(received[0].body["player"]["name"], "Alice");
+        assert_eq!(received[0].body["game"]["label"], "Counter-Strike 2");
+    }
+
+    #[actix_web::test]
+    async fn send_reports_failed_status() {
+        let (url, _) = mock(500);
+        let generic = webhook(json!({ "kind": "generic", "url": url }));
+
+        let error = send(Client::new(), &generic.target, json!({})).await.unwrap_err();
+
+        assert!(error.starts_with("500"), "{error}");
+        assert!(error.ends_with("nope"), "{error}");
+    }
+}
\ No newline at end of file
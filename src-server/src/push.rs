@@ -0,0 +1,240 @@
+use std::{sync::LazyLock, time::{Duration, SystemTime, UNIX_EPOCH}};
+
+use log::warn;
+use prometheus::{proto::{MetricFamily, MetricType}, TextEncoder};
+use reqwest::{Client, header::CONTENT_TYPE};
+
+use crate::{config::{PUSHGATEWAY_URL, PUSH_JOB, REMOTE_WRITE_URL}, metrics::MetricsContext};
+
+static CLIENT: LazyLock<Client> = LazyLock::new(|| Client::builder()
+    .timeout(Duration::from_secs(10))
+    .build()
+    .expect("could not build push client"));
+
+pub fn is_enabled() -> bool {
+    PUSHGATEWAY_URL.is_some() || REMOTE_WRITE_URL.is_some()
+}
+
+/// replaces the job's metrics on the Pushgateway with the current ones
+async fn push_gateway(url: &str, families: &[MetricFamily]) -> Result<(), String> {
+    let mut buffer = String::new();
+
+    TextEncoder::new().encode_utf8(families, &mut buffer)
+        .map_err(|e| format!("failed to encode metrics: {e}"))?;
+
+    let res = CLIENT.put(format!("{}/metrics/job/{}", url.trim_end_matches('/'), *PUSH_JOB))
+        .header(CONTENT_TYPE, "text/plain; version=0.0.4")
+        .body(buffer)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !res.status().is_success() {
+        return Err(format!("{}: {}", res.status(), res.text().await.unwrap_or_default()));
+    }
+
+    Ok(())
+}
+
+fn put_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+
+    buf.push(value as u8);
+}
+
+/// appends a length delimited field (wire type 2)
+fn put_bytes(buf: &mut Vec<u8>, field: u64, bytes: &[u8]) {
+    put_varint(buf, field << 3 | 2);
+    put_varint(buf, bytes.len() as u64);
+    buf.extend_from_slice(bytes);
+}
+
+/// encodes a `prometheus.WriteRequest` by hand, it only consists of labels and samples
+fn encode_write_request(families: &[MetricFamily], timestamp: i64) -> Vec<u8> {
+    let mut request = vec![];
+
+    for family in families {
+        for metric in &family.metric {
+            let value = match family.type_() {
+                MetricType::COUNTER => metric.counter.value(),
+                MetricType::GAUGE => metric.gauge.value(),
+                _ => continue
+            };
+            let mut labels: Vec<(&str, &str)> = metric.label.iter()
+                .map(|l| (l.name(), l.value()))
+                .collect();
+
+            labels.push(("__name__", family.name()));
+            // remote write expects labels sorted by name
+            labels.sort();
+
+            let mut series = vec![];
+
+            for (name, value) in labels {
+                let mut label = vec![];
+
+                put_bytes(&mut label, 1, name.as_bytes());
+                put_bytes(&mut label, 2, value.as_bytes());
+                put_bytes(&mut series, 1, &label);
+            }
+
+            let mut sample = vec![];
+
+            // value is a double (wire type 1), timestamp an int64 (wire type 0)
+            put_varint(&mut sample, 1 << 3 | 1);
+            sample.extend_from_slice(&value.to_le_bytes());
+            put_varint(&mut sample, 2 << 3);
+            put_varint(&mut sample, timestamp as u64);
+            put_bytes(&mut series, 2, &sample);
+
+            put_bytes(&mut request, 1, &series);
+        }
+    }
+
+    request
+}
+
+async fn remote_write(url: &str, families: &[MetricFamily]) -> Result<(), String> {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)
+        .map_err(|e| e.to_string())?
+        .as_millis() as i64;
+    let body = snap::raw::Encoder::new()
+        .compress_vec(&encode_write_request(families, timestamp))
+        .map_err(|e| format!("failed to compress metrics: {e}"))?;
+
+    let res = CLIENT.post(url)
+        .header(CONTENT_TYPE, "application/x-protobuf")
+        .header("Content-Encoding", "snappy")
+        .header("X-Prometheus-Remote-Write-Version", "0.1.0")
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !res.status().is_success() {
+        return Err(format!("{}: {}", res.status(), res.text().await.unwrap_or_default()));
+    }
+
+    Ok(())
+}
+
+/// sends the current metrics to every configured receiver
+pub async fn push(metrics: &MetricsContext) {
+    let families = metrics.gather();
+
+    if let Some(url) = PUSHGATEWAY_URL.as_ref() && let Err(e) = push_gateway(url, &families).await {
+        warn!("failed to push metrics to the Pushgateway: {e}");
+    }
+
+    if let Some(url) = REMOTE_WRITE_URL.as_ref() && let Err(e) = remote_write(url, &families).await {
+        warn!("failed to remote write metrics: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prometheus::{IntCounterVec, Gauge, Histogram, HistogramOpts, Opts, Registry};
+    use prost::Message;
+
+    use super::*;
+
+    #[derive(Message)]
+    struct WriteRequest {
+        #[prost(message, repeated, tag = "1")]
+        timeseries: Vec<TimeSeries>
+    }
+
+    #[derive(Message)]
+    struct TimeSeries {
+        #[prost(message, repeated, tag = "1")]
+        labels: Vec<Label>,
+        #[prost(message, repeated, tag = "2")]
+        samples: Vec<Sample>
+    }
+
+    #[derive(Message)]
+    struct Label {
+        #[prost(string, tag = "1")]
+        name: String,
+        #[prost(string, tag = "2")]
+        value: String
+    }
+
+    #[derive(Message)]
+    struct Sample {
+        #[prost(double, tag = "1")]
+        value: f64,
+        #[prost(int64, tag = "2")]
+        timestamp: i64
+    }
+
+    fn labels(series: &TimeSeries) -> Vec<(&str, &str)> {
+        series.labels.iter().map(|l| (l.name.as_str(), l.value.as_str())).collect()
+    }
+
+    #[test]
+    fn write_request_decodes_as_protobuf() {
+        let registry = Registry::new();
+        let played = IntCounterVec::new(Opts::new("lan_played_total", "played"), &["player", "game"]).unwrap();
+        let online = Gauge::new("lan_online", "online").unwrap();
+        let latency = Histogram::with_opts(HistogramOpts::new("lan_latency", "latency")).unwrap();
+
+        registry.register(Box::new(played.clone())).unwrap();
+        registry.register(Box::new(online.clone())).unwrap();
+        registry.register(Box::new(latency.clone())).unwrap();
+        played.with_label_values(&["Alice", "Counter-Strike 2"]).inc_by(3);
+        online.set(2.5);
+        latency.observe(1.0);
+
+        let timestamp = 1_700_000_000_123;
+        let compressed = snap::raw::Encoder::new().compress_vec(&encode_write_request(&registry.gather(), timestamp)).unwrap();
+        let decompressed = snap::raw::Decoder::new().decompress_vec(&compressed).unwrap();
+        let request = WriteRequest::decode(decompressed.as_slice()).unwrap();
+
+        // histograms are not sent
+        assert_eq!(request.timeseries.len(), 2);
+
+        let online = request.timeseries.iter().find(|s| labels(s) == [("__name__", "lan_online")]).unwrap();
+        let played = request.timeseries.iter().find(|s| labels(s) == [("__name__", "lan_played_total"), ("game", "Counter-Strike 2"), ("player", "Alice")]).unwrap();
+
+        assert_eq!(online.samples.len(), 1);
+        assert_eq!(online.samples[0].value, 2.5);
+        assert_eq!(online.samples[0].timestamp, timestamp);
+        assert_eq!(played.samples[0].value, 3.0);
+        assert_eq!(played.samples[0].timestamp, timestamp);
+    }
+
+    #[test]
+    fn write_request_matches_known_bytes() {
+        let registry = Registry::new();
+        let gauge = Gauge::new("up", "up").unwrap();
+
+        registry.register(Box::new(gauge.clone())).unwrap();
+        gauge.set(1.0);
+
+        let expected = [
+            // timeseries, 30 bytes
+            0x0a, 0x1e,
+            // label, 14 bytes: name "__name__", value "up"
+            0x0a, 0x0e, 0x0a, 0x08, b'_', b'_', b'n', b'a', b'm', b'e', b'_', b'_', 0x12, 0x02, b'u', b'p',
+            // sample, 12 bytes: value 1.0, timestamp 1000
+            0x12, 0x0c, 0x09, 0, 0, 0, 0, 0, 0, 0xf0, 0x3f, 0x10, 0xe8, 0x07
+        ];
+
+        assert_eq!(encode_write_request(&registry.gather(), 1000), expected);
+    }
+
+    #[test]
+    fn varints_use_seven_bits_per_byte() {
+        let mut buf = vec![];
+
+        put_varint(&mut buf, 0);
+        put_varint(&mut buf, 300);
+        put_varint(&mut buf, -1_i64 as u64);
+
+        assert_eq!(buf, [0x00, 0xac, 0x02, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01]);
+    }
+}
\ No newline at end of file
@@ -1,2 +1,11 @@
 pub mod now_playing;
-pub mod games;
\ No newline at end of file
+pub mod games;
+pub mod history;
+pub mod leaderboards;
+pub mod suggestions;
+pub mod icons;
+pub mod unknown_processes;
+pub mod export;
+pub mod recap;
+pub mod timeline;
+pub mod coplay;
\ No newline at end of file
@@ -0,0 +1,200 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use chrono::{DateTime, FixedOffset, Local, TimeDelta};
+use common::{game::Game, response::{coplay::{Buddy, CoPlayEdge, CoPlayGraph}, history::Session, now_playing::Player}};
+
+use crate::{api::SharedData, repo::history::get_sessions};
+
+const MAX_BUDDIES: usize = 5;
+
+#[derive(Default)]
+struct Together {
+    time: TimeDelta,
+    games: BTreeSet<String>
+}
+
+/// time both sessions ran within `from` and `to`
+fn overlap(a: &Session, b: &Session, from: Option<DateTime<Local>>, to: Option<DateTime<Local>>, now: DateTime<Local>) -> TimeDelta {
+    let start = a.start.max(b.start);
+    let start = from.map_or(start, |from| start.max(from));
+    let end = a.end.unwrap_or(now).min(b.end.unwrap_or(now));
+    let end = to.map_or(end, |to| end.min(to));
+
+    (end - start).max(TimeDelta::zero())
+}
+
+/// shared time per pair of player ids, the smaller id first,
+/// sweeps each game's sessions by start so only sessions still running are compared
+fn together(sessions: &[Session], from: Option<DateTime<Local>>, to: Option<DateTime<Local>>) -> HashMap<(String, String), Together> {
+    let now = Local::now();
+    let mut by_game: HashMap<&str, Vec<&Session>> = HashMap::new();
+    let mut pairs: HashMap<(String, String), Together> = HashMap::new();
+
+    for session in sessions {
+        by_game.entry(&session.game.name).or_default().push(session);
+    }
+
+    for (game, mut sessions) in by_game {
+        let mut active: Vec<&Session> = vec![];
+
+        sessions.sort_by_key(|s| s.start);
+
+        for b in sessions {
+            active.retain(|a| a.end.unwrap_or(now) > b.start);
+
+            for a in active.iter().filter(|a| a.player.id != b.player.id) {
+                let time = overlap(a, b, from, to, now);
+
+                if time <= TimeDelta::zero() {
+                    continue;
+                }
+
+                let key = if a.player.id < b.player.id {
+                    (a.player.id.clone(), b.player.id.clone())
+                } else {
+                    (b.player.id.clone(), a.player.id.clone())
+                };
+                let pair = pairs.entry(key).or_default();
+
+                pair.time += time;
+                pair.games.insert(game.to_string());
+            }
+
+            active.push(b);
+        }
+    }
+
+    pairs
+}
+
+/// players as nodes, edges weighted by the time they spent in the same game
+pub async fn get_graph(data: &SharedData, from: Option<DateTime<FixedOffset>>, to: Option<DateTime<FixedOffset>>) -> CoPlayGraph {
+    let sessions = get_sessions(data, from, to).await;
+    let pairs = together(&sessions, from.map(|from| from.with_timezone(&Local)), to.map(|to| to.with_timezone(&Local)));
+    let mut nodes: Vec<Player> = sessions.into_iter()
+        .map(|s| s.player)
+        .collect::<HashSet<Player>>()
+        .into_iter()
+        .collect();
+    let mut edges: Vec<CoPlayEdge> = pairs.into_iter()
+        .map(|((source, target), pair)| CoPlayEdge {
+            source,
+            target,
+            seconds: pair.time.as_seconds_f64(),
+            games: pair.games.into_iter().collect()
+        })
+        .collect();
+
+    nodes.sort_by(|a, b| a.name.cmp(&b.name));
+    edges.sort_by(|a, b| b.seconds.total_cmp(&a.seconds).then_with(|| (&a.source, &a.target).cmp(&(&b.source, &b.target))));
+
+    CoPlayGraph {
+        nodes,
+        edges
+    }
+}
+
+/// players `player` spent the most time with, followed by players sharing their taste in games
+pub async fn get_buddies(data: &SharedData, player: &str) -> Vec<Buddy> {
+    let sessions = get_sessions(data, None, None).await;
+    let pairs = together(&sessions, None, None);
+    let mut games: HashMap<&Player, HashMap<&str, &Game>> = HashMap::new();
+
+    for session in &sessions {
+        games.entry(&session.player).or_default().insert(&session.game.name, &session.game);
+    }
+
+    let Some(own) = games.iter().find(|(p, _)| p.id == player).map(|(_, games)| games.clone()) else {
+        return vec![];
+    };
+
+    let mut buddies: Vec<Buddy> = games.into_iter()
+        .filter(|(other, _)| other.id != player)
+        .map(|(other, theirs)| {
+            let key = if player < other.id.as_str() {
+                (player.to_string(), other.id.clone())
+            } else {
+                (other.id.clone(), player.to_string())
+            };
+            let mut common: Vec<Game> = theirs.into_iter()
+                .filter(|(name, _)| own.contains_key(name))
+                .map(|(_, game)| game.clone())
+                .collect();
+
+            common.sort_by(|a, b| a.label.cmp(&b.label));
+
+            Buddy {
+                player: other.clone(),
+                seconds: pairs.get(&key).map_or(0.0, |pair| pair.time.as_seconds_f64()),
+                games: common
+            }
+        })
+        .filter(|buddy| buddy.seconds > 0.0 || !buddy.games.is_empty())
+        .collect();
+
+    buddies.sort_by(|a, b| b.seconds.total_cmp(&a.seconds)
+        .then_with(|| b.games.len().cmp(&a.games.len()))
+        .then_with(|| a.player.name.cmp(&b.player.name)));
+    buddies.truncate(MAX_BUDDIES);
+
+    buddies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(minutes: i64) -> DateTime<Local> {
+        DateTime::from_timestamp(minutes * 60, 0).unwrap().with_timezone(&Local)
+    }
+
+    fn session(player: &str, game: &str, start: i64, end: Option<i64>) -> Session {
+        Session {
+            player: Player {
+                id: player.to_string(),
+                name: player.to_string()
+            },
+            game: Game {
+                name: game.to_string(),
+                label: game.to_string(),
+                icon: String::new()
+            },
+            start: at(start),
+            end: end.map(at)
+        }
+    }
+
+    #[test]
+    fn overlap_is_clipped_to_the_range() {
+        let a = session("1", "a", 0, Some(60));
+        let b = session("2", "a", 30, Some(90));
+
+        assert_eq!(overlap(&a, &b, None, None, at(100)), TimeDelta::minutes(30));
+        assert_eq!(overlap(&a, &b, Some(at(40)), None, at(100)), TimeDelta::minutes(20));
+        assert_eq!(overlap(&a, &b, None, Some(at(35)), at(100)), TimeDelta::minutes(5));
+        assert_eq!(overlap(&a, &session("2", "a", 60, None), None, None, at(100)), TimeDelta::zero());
+    }
+
+    #[test]
+    fn together_sums_shared_time_per_pair() {
+        let sessions = vec![
+            session("2", "a", 0, Some(60)),
+            session("1", "a", 30, Some(120)),
+            session("3", "a", 70, Some(80)),
+            session("1", "b", 0, Some(60)),
+            session("2", "b", 50, Some(55)),
+            // same player twice, and a game nobody else played at that time
+            session("1", "a", 130, Some(140)),
+            session("3", "c", 0, Some(60))
+        ];
+        let pairs = together(&sessions, None, None);
+
+        assert_eq!(pairs.len(), 2);
+
+        let first = &pairs[&("1".to_string(), "2".to_string())];
+
+        assert_eq!(first.time, TimeDelta::minutes(35));
+        assert_eq!(first.games.iter().collect::<Vec<_>>(), ["a", "b"]);
+        assert_eq!(pairs[&("1".to_string(), "3".to_string())].time, TimeDelta::minutes(10));
+    }
+}
\ No newline at end of file
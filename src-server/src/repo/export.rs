@@ -0,0 +1,238 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, FixedOffset, Local, TimeDelta};
+use common::{game::Game, response::{export::{ExportResponse, ExportTable, GameTotal, PlayerTotal}, history::Session, now_playing::Player}};
+
+use crate::{api::SharedData, repo::history::get_sessions};
+
+/// start and end of some playtime
+pub type Interval = (DateTime<Local>, DateTime<Local>);
+
+#[derive(Default)]
+struct Totals {
+    time: TimeDelta,
+    /// players only, merged so parallel games count once
+    intervals: Vec<Interval>,
+    sessions: usize,
+    others: HashSet<String>
+}
+
+/// start and end of the part of `session` within `from` and `to`, running sessions end now
+fn clip(session: &Session, from: Option<DateTime<Local>>, to: Option<DateTime<Local>>) -> Option<Interval> {
+    let start = from.map_or(session.start, |from| session.start.max(from));
+    let end = session.end.unwrap_or(Local::now());
+    let end = to.map_or(end, |to| end.min(to));
+
+    (end > start).then_some((start, end))
+}
+
+/// the part of `session` within `from` and `to`, running sessions end now
+pub fn clipped(session: &Session, from: Option<DateTime<Local>>, to: Option<DateTime<Local>>) -> TimeDelta {
+    clip(session, from, to).map_or(TimeDelta::zero(), |(start, end)| end - start)
+}
+
+/// joins overlapping or touching intervals, sorted by start
+pub fn merge(mut intervals: Vec<Interval>) -> Vec<Interval> {
+    let mut merged: Vec<Interval> = vec![];
+
+    intervals.sort();
+
+    for (start, end) in intervals {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end))
+        }
+    }
+
+    merged
+}
+
+/// time a player spent playing within `from` and `to`, games running at once count once
+pub fn playtime<'a>(sessions: impl IntoIterator<Item = &'a Session>, from: Option<DateTime<Local>>, to: Option<DateTime<Local>>) -> TimeDelta {
+    merge(sessions.into_iter().filter_map(|s| clip(s, from, to)).collect())
+        .into_iter()
+        .map(|(start, end)| end - start)
+        .sum()
+}
+
+pub async fn get_export(data: &SharedData, from: Option<DateTime<FixedOffset>>, to: Option<DateTime<FixedOffset>>) -> ExportResponse {
+    let sessions = get_sessions(data, from, to).await;
+    let from = from.map(|from| from.with_timezone(&Local));
+    let to = to.map(|to| to.with_timezone(&Local));
+    let mut players: HashMap<Player, Totals> = HashMap::new();
+    let mut games: HashMap<Game, Totals> = HashMap::new();
+
+    for session in &sessions {
+        let time = clipped(session, from, to);
+        let by_player = players.entry(session.player.clone()).or_default();
+
+        by_player.intervals.extend(clip(session, from, to));
+        by_player.sessions += 1;
+        by_player.others.insert(session.game.name.clone());
+
+        let by_game = games.entry(session.game.clone()).or_default();
+
+        by_game.time += time;
+        by_game.sessions += 1;
+        by_game.others.insert(session.player.id.clone());
+    }
+
+    let mut players: Vec<PlayerTotal> = players.into_iter()
+        .map(|(player, totals)| PlayerTotal {
+            player,
+            seconds: merge(totals.intervals).into_iter().map(|(start, end)| end - start).sum::<TimeDelta>().as_seconds_f64(),
+            sessions: totals.sessions,
+            games: totals.others.len()
+        })
+        .collect();
+    let mut games: Vec<GameTotal> = games.into_iter()
+        .map(|(game, totals)| GameTotal {
+            game,
+            seconds: totals.time.as_seconds_f64(),
+            sessions: totals.sessions,
+            players: totals.others.len()
+        })
+        .collect();
+
+    players.sort_by(|a, b| b.seconds.total_cmp(&a.seconds).then_with(|| a.player.name.cmp(&b.player.name)));
+    games.sort_by(|a, b| b.seconds.total_cmp(&a.seconds).then_with(|| a.game.label.cmp(&b.game.label)));
+
+    ExportResponse {
+        from,
+        to,
+        sessions,
+        players,
+        games
+    }
+}
+
+/// names are chosen by players, so cells a spreadsheet would evaluate as formula are prefixed with `'`
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{value}")
+    } else {
+        value.to_string()
+    };
+
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+fn csv_row(fields: &[&str]) -> String {
+    let mut row = fields.iter()
+        .map(|f| csv_field(f))
+        .collect::<Vec<String>>()
+        .join(",");
+
+    row.push_str("\r\n");
+    row
+}
+
+/// renders one table of `export` as RFC 4180 csv with a header row
+pub fn to_csv(export: &ExportResponse, table: ExportTable) -> String {
+    let mut out = String::new();
+
+    match table {
+        ExportTable::Sessions => {
+            out.push_str(&csv_row(&["player_id", "player_name", "game", "game_label", "start", "end", "seconds"]));
+
+            for session in &export.sessions {
+                let end = session.end.map(|end| end.to_rfc3339()).unwrap_or_default();
+                // clipped like the totals, so the tables add up for sessions crossing `from` or `to`
+                let seconds = clipped(session, export.from, export.to).as_seconds_f64();
+
+                out.push_str(&csv_row(&[&session.player.id, &session.player.name, &session.game.name, &session.game.label, &session.start.to_rfc3339(), &end, &format!("{seconds:.0}")]));
+            }
+        },
+        ExportTable::Players => {
+            out.push_str(&csv_row(&["player_id", "player_name", "seconds", "sessions", "games"]));
+
+            for total in &export.players {
+                out.push_str(&csv_row(&[&total.player.id, &total.player.name, &format!("{:.0}", total.seconds), &total.sessions.to_string(), &total.games.to_string()]));
+            }
+        },
+        ExportTable::Games => {
+            out.push_str(&csv_row(&["game", "game_label", "seconds", "sessions", "players"]));
+
+            for total in &export.games {
+                out.push_str(&csv_row(&[&total.game.name, &total.game.label, &format!("{:.0}", total.seconds), &total.sessions.to_string(), &total.players.to_string()]));
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(minutes: i64) -> DateTime<Local> {
+        DateTime::from_timestamp(minutes * 60, 0).unwrap().with_timezone(&Local)
+    }
+
+    #[test]
+    fn csv_field_quotes_and_defuses_formulas() {
+        assert_eq!(csv_field("Alice"), "Alice");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("=HYPERLINK(\"x\")"), "\"'=HYPERLINK(\"\"x\"\")\"");
+        assert_eq!(csv_field("+1"), "'+1");
+        assert_eq!(csv_field("-1"), "'-1");
+        assert_eq!(csv_field("@sum"), "'@sum");
+        assert_eq!(csv_field("\tx"), "'\tx");
+        assert_eq!(csv_field("\rx"), "\"'\rx\"");
+    }
+
+    #[test]
+    fn session_seconds_are_clipped_like_totals() {
+        let session = Session {
+            player: Player {
+                id: "1".to_string(),
+                name: "Alice".to_string()
+            },
+            game: Game {
+                name: "a".to_string(),
+                label: "A".to_string(),
+                icon: String::new()
+            },
+            start: at(0),
+            end: Some(at(60))
+        };
+        let export = ExportResponse {
+            from: Some(at(30)),
+            to: Some(at(50)),
+            sessions: vec![session],
+            players: vec![],
+            games: vec![]
+        };
+        let csv = to_csv(&export, ExportTable::Sessions);
+
+        assert!(csv.lines().nth(1).unwrap().ends_with(",1200"));
+    }
+
+    #[test]
+    fn playtime_counts_parallel_games_once() {
+        let session = |game: &str, start: i64, end: i64| Session {
+            player: Player {
+                id: "1".to_string(),
+                name: "Alice".to_string()
+            },
+            game: Game {
+                name: game.to_string(),
+                label: game.to_string(),
+                icon: String::new()
+            },
+            start: at(start),
+            end: Some(at(end))
+        };
+        let sessions = [session("a", 0, 60), session("b", 30, 90), session("c", 90, 100), session("d", 120, 130)];
+
+        assert_eq!(playtime(&sessions, None, None), TimeDelta::minutes(110));
+        assert_eq!(playtime(&sessions, Some(at(50)), Some(at(125))), TimeDelta::minutes(55));
+        assert_eq!(merge(vec![(at(10), at(20)), (at(0), at(5)), (at(5), at(8))]), [(at(0), at(8)), (at(10), at(20))]);
+    }
+}
\ No newline at end of file
@@ -1,25 +1,105 @@
-use std::{fs::File, io::Read, sync::LazyLock};
+use std::{fmt::Write as _, fs, sync::{LazyLock, RwLock}, time::SystemTime};
 
 use common::game::Game;
+use log::{info, warn};
+use serde::Serialize;
+use serde_json::ser::PrettyFormatter;
+use sha2::{Digest, Sha256};
 
 use crate::config::GAMES_FILE;
 
-static GAMES: LazyLock<Vec<Game>> = LazyLock::new(|| {
-    let mut buf = String::new();
-    
-    File::open(GAMES_FILE.as_str())
-        .expect("could not find games list")
-        .read_to_string(&mut buf)
-        .expect("could not read games list");
+pub struct Catalogue {
+    pub games: Vec<Game>,
+    /// changes whenever the content changes, used as `ETag`
+    pub etag: String,
+    pub modified: SystemTime
+}
+
+impl Catalogue {
+    fn new(games: Vec<Game>, modified: SystemTime) -> Catalogue {
+        let mut hasher = Sha256::new();
+        let mut etag = String::new();
+
+        hasher.update(serde_json::to_vec(&games).expect("could not serialize games list"));
+        write!(etag, "\"{:x}\"", hasher.finalize()).expect("could not hash games list");
+
+        Catalogue {
+            games,
+            etag,
+            modified
+        }
+    }
+}
+
+fn read_catalogue() -> Result<Catalogue, String> {
+    let buf = fs::read_to_string(GAMES_FILE.as_str())
+        .map_err(|e| format!("could not read games list: {e}"))?;
+    let modified = fs::metadata(GAMES_FILE.as_str())
+        .and_then(|m| m.modified())
+        .unwrap_or(SystemTime::now());
+    let games = serde_json::from_str::<Vec<Game>>(&buf)
+        .map_err(|e| format!("could not parse games list: {e}"))?;
 
-    serde_json::from_str::<Vec<Game>>(&buf)
-        .expect("could not parse games list")
+    Ok(Catalogue::new(games, modified))
+}
+
+static GAMES: LazyLock<RwLock<Catalogue>> = LazyLock::new(|| {
+    RwLock::new(read_catalogue().expect("could not load games list"))
 });
 
-pub fn get_games() -> &'static Vec<Game> {
-    &GAMES
+pub fn get_catalogue<T>(f: impl FnOnce(&Catalogue) -> T) -> T {
+    f(&GAMES.read().expect("games lock poisoned"))
+}
+
+pub fn get_game(name: &str) -> Option<Game> {
+    get_catalogue(|c| c.games.iter()
+        .find(|g| g.name.as_str() == name)
+        .cloned())
 }
 
-pub fn get_game(name: &str) -> Option<&Game> {
-    GAMES.iter().find(|g| g.name.as_str() == name)
+/// picks up manual edits of `GAMES_FILE` without a restart
+pub fn reload() {
+    let modified = fs::metadata(GAMES_FILE.as_str()).and_then(|m| m.modified());
+
+    if modified.is_ok_and(|m| m <= get_catalogue(|c| c.modified)) {
+        return;
+    }
+
+    match read_catalogue() {
+        Ok(catalogue) => {
+            let Ok(mut games_lock) = GAMES.write() else {
+                return;
+            };
+
+            if catalogue.etag != games_lock.etag {
+                info!("reloaded games list with {} game(s)", catalogue.games.len());
+            }
+
+            *games_lock = catalogue;
+        },
+        Err(e) => warn!("failed to reload games list: {e}")
+    }
+}
+
+/// adds `game` to the catalogue and writes it back to `GAMES_FILE`
+pub fn add_game(game: Game) -> Result<(), String> {
+    let mut games_lock = GAMES.write().map_err(|e| e.to_string())?;
+
+    if games_lock.games.contains(&game) {
+        return Err(format!("{} is already in the catalogue", game.name));
+    }
+
+    let mut games = games_lock.games.clone();
+
+    games.push(game);
+
+    let mut buf = vec![];
+    let mut serializer = serde_json::Serializer::with_formatter(&mut buf, PrettyFormatter::with_indent(b"    "));
+
+    games.serialize(&mut serializer).map_err(|e| e.to_string())?;
+    fs::write(GAMES_FILE.as_str(), buf).map_err(|e| format!("could not write games list: {e}"))?;
+
+    *games_lock = Catalogue::new(games, SystemTime::now());
+
+    Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,231 @@
+use std::collections::{HashMap, VecDeque};
+
+use chrono::{DateTime, FixedOffset, Local, TimeDelta};
+use common::{game::Game, response::{history::Session, now_playing::Player}};
+
+use crate::{api::SharedData, config::{HISTORY_MAX_SESSIONS, HISTORY_RETENTION_DAYS}};
+
+#[derive(Default)]
+pub struct History {
+    /// finished sessions in the order they ended
+    closed: VecDeque<Session>,
+    /// running sessions by player id, all a heartbeat has to look at
+    open: HashMap<String, Vec<Session>>
+}
+
+impl History {
+    pub fn from_sessions(sessions: Vec<Session>) -> History {
+        let mut history = History::default();
+        let (mut closed, open): (Vec<Session>, Vec<Session>) = sessions.into_iter().partition(|s| s.end.is_some());
+
+        closed.sort_by_key(|s| s.end);
+        history.closed = closed.into();
+
+        for session in open {
+            history.open.entry(session.player.id.clone()).or_default().push(session);
+        }
+
+        history.prune();
+
+        history
+    }
+
+    pub fn sessions(&self) -> impl Iterator<Item = &Session> {
+        self.closed.iter().chain(self.open.values().flatten())
+    }
+
+    /// every session ordered by start
+    pub fn to_vec(&self) -> Vec<Session> {
+        let mut sessions: Vec<Session> = self.sessions().cloned().collect();
+
+        sessions.sort_by_key(|s| s.start);
+
+        sessions
+    }
+
+    fn finish(&mut self, mut session: Session, end: DateTime<Local>) {
+        session.end = Some(end);
+        self.closed.push_back(session);
+    }
+
+    /// drops finished sessions beyond `HISTORY_RETENTION_DAYS` and `HISTORY_MAX_SESSIONS`
+    fn prune(&mut self) {
+        let cutoff = (*HISTORY_RETENTION_DAYS > 0).then(|| Local::now() - TimeDelta::days(i64::from(*HISTORY_RETENTION_DAYS)));
+
+        self.prune_to(cutoff, *HISTORY_MAX_SESSIONS);
+    }
+
+    fn prune_to(&mut self, cutoff: Option<DateTime<Local>>, max: usize) {
+        if let Some(cutoff) = cutoff {
+            while self.closed.front().is_some_and(|s| s.end.is_some_and(|end| end < cutoff)) {
+                self.closed.pop_front();
+            }
+        }
+
+        let excess = self.closed.len().saturating_sub(max);
+
+        self.closed.drain(..excess);
+    }
+}
+
+pub async fn record(data: &SharedData, player: &Player, games: &[Game]) {
+    let mut history_lock = data.history.lock().await;
+    let now = Local::now();
+    let open = history_lock.open.remove(&player.id).unwrap_or_default();
+    let (running, stopped): (Vec<Session>, Vec<Session>) = open.into_iter().partition(|s| games.contains(&s.game));
+    let started: Vec<Session> = games.iter()
+        .filter(|g| !running.iter().any(|s| s.game.name == g.name))
+        .map(|game| Session {
+            player: player.clone(),
+            game: game.clone(),
+            start: now,
+            end: None
+        })
+        .collect();
+
+    for session in stopped {
+        history_lock.finish(session, now);
+    }
+
+    let open: Vec<Session> = running.into_iter().chain(started).collect();
+
+    if !open.is_empty() {
+        history_lock.open.insert(player.id.clone(), open);
+    }
+
+    history_lock.prune();
+}
+
+pub async fn close(data: &SharedData, player: &String) {
+    close_at(data, player, Local::now()).await;
+}
+
+pub async fn close_at(data: &SharedData, player: &String, now: DateTime<Local>) {
+    let mut history_lock = data.history.lock().await;
+
+    for session in history_lock.open.remove(player).unwrap_or_default() {
+        history_lock.finish(session, now);
+    }
+
+    history_lock.prune();
+}
+
+pub async fn get_sessions(data: &SharedData, from: Option<DateTime<FixedOffset>>, to: Option<DateTime<FixedOffset>>) -> Vec<Session> {
+    let history_lock = data.history.lock().await;
+    let mut sessions: Vec<Session> = history_lock.sessions()
+        .filter(|s| from.is_none_or(|from| s.end.is_none_or(|end| end >= from)))
+        .filter(|s| to.is_none_or(|to| s.start <= to))
+        .cloned()
+        .collect();
+
+    sessions.sort_by_key(|s| s.start);
+
+    sessions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(id: &str) -> Player {
+        Player {
+            id: id.to_string(),
+            name: id.to_string()
+        }
+    }
+
+    fn game(name: &str) -> Game {
+        Game {
+            name: name.to_string(),
+            label: name.to_string(),
+            icon: String::new()
+        }
+    }
+
+    fn session(player: &str, game: &str, hours_ago: i64, ended: Option<i64>) -> Session {
+        let now = Local::now();
+
+        Session {
+            player: self::player(player),
+            game: self::game(game),
+            start: now - TimeDelta::hours(hours_ago),
+            end: ended.map(|hours_ago| now - TimeDelta::hours(hours_ago))
+        }
+    }
+
+    fn running(data: &History, player: &str) -> Vec<String> {
+        let mut games: Vec<String> = data.open.get(player).into_iter().flatten().map(|s| s.game.name.clone()).collect();
+
+        games.sort();
+
+        games
+    }
+
+    #[actix_web::test]
+    async fn record_starts_and_finishes_sessions() {
+        let data = SharedData::new();
+
+        record(&data, &player("a"), &[game("cs2"), game("aoe")]).await;
+        record(&data, &player("b"), &[game("cs2")]).await;
+        record(&data, &player("a"), &[game("cs2"), game("bar")]).await;
+
+        let history = data.history.lock().await;
+
+        assert_eq!(running(&history, "a"), ["bar", "cs2"]);
+        assert_eq!(running(&history, "b"), ["cs2"]);
+        assert_eq!(history.closed.len(), 1);
+        assert_eq!(history.closed[0].game.name, "aoe");
+        assert!(history.closed[0].end.is_some());
+    }
+
+    #[actix_web::test]
+    async fn record_without_games_and_close_finish_everything() {
+        let data = SharedData::new();
+
+        record(&data, &player("a"), &[game("cs2")]).await;
+        record(&data, &player("b"), &[game("cs2"), game("aoe")]).await;
+        record(&data, &player("a"), &[]).await;
+        close(&data, &"b".to_string()).await;
+
+        let history = data.history.lock().await;
+
+        assert!(history.open.is_empty());
+        assert_eq!(history.closed.len(), 3);
+        assert!(history.sessions().all(|s| s.end.is_some()));
+    }
+
+    #[test]
+    fn from_sessions_indexes_running_sessions() {
+        let history = History::from_sessions(vec![
+            session("a", "cs2", 3, Some(2)),
+            session("a", "aoe", 1, None),
+            session("b", "cs2", 5, Some(4))
+        ]);
+
+        assert_eq!(running(&history, "a"), ["aoe"]);
+        // ordered by end, the oldest first
+        assert_eq!(history.closed.iter().map(|s| s.player.id.as_str()).collect::<Vec<&str>>(), ["b", "a"]);
+        assert_eq!(history.to_vec().iter().map(|s| s.game.name.as_str()).collect::<Vec<&str>>(), ["cs2", "cs2", "aoe"]);
+    }
+
+    #[test]
+    fn prune_drops_old_and_excess_sessions() {
+        let mut history = History::from_sessions(vec![
+            session("a", "old", 50, Some(49)),
+            session("a", "older", 60, Some(59)),
+            session("b", "recent", 3, Some(2)),
+            session("c", "latest", 2, Some(1)),
+            session("d", "running", 100, None)
+        ]);
+
+        history.prune_to(Some(Local::now() - TimeDelta::hours(24)), 10);
+
+        assert_eq!(history.closed.iter().map(|s| s.game.name.as_str()).collect::<Vec<&str>>(), ["recent", "latest"]);
+
+        history.prune_to(None, 1);
+
+        assert_eq!(history.closed.iter().map(|s| s.game.name.as_str()).collect::<Vec<&str>>(), ["latest"]);
+        // running sessions are never dropped
+        assert_eq!(running(&history, "d"), ["running"]);
+    }
+}
\ No newline at end of file
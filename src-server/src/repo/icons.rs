@@ -0,0 +1,97 @@
+use std::{collections::HashMap, fmt::Write as _, io::Cursor, path::{Component, Path}, sync::{LazyLock, Mutex}, time::SystemTime};
+
+use image::{imageops::{self, FilterType}, ImageFormat, RgbaImage};
+use sha2::{Digest, Sha256};
+
+use crate::config::ICONS_DIR;
+
+/// the only edge lengths served, so the cache holds at most this many conversions per icon
+pub const SIZES: &[u32] = &[32, 64, 128, 256];
+
+#[derive(Clone)]
+pub struct Icon {
+    pub bytes: Vec<u8>,
+    /// hash of the encoded PNG, used as `ETag`
+    pub etag: String,
+    /// modification time of the source file
+    pub modified: SystemTime
+}
+
+/// converted icons by source file and edge length
+static ICONS: LazyLock<Mutex<HashMap<(String, u32), Icon>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn convert(path: &Path, size: u32, modified: SystemTime) -> Result<Icon, String> {
+    let resized = image::open(path)
+        .map_err(|e| format!("could not decode {}: {e}", path.display()))?
+        .resize(size, size, FilterType::Lanczos3);
+    // non-square sources are centered on a transparent square
+    let mut image = RgbaImage::new(size, size);
+    let mut bytes = vec![];
+
+    imageops::overlay(&mut image, &resized.to_rgba8(), i64::from((size - resized.width()) / 2), i64::from((size - resized.height()) / 2));
+
+    image.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+        .map_err(|e| format!("could not encode {}: {e}", path.display()))?;
+
+    let mut hasher = Sha256::new();
+    let mut etag = String::new();
+
+    hasher.update(&bytes);
+    write!(etag, "\"{:x}\"", hasher.finalize()).map_err(|e| e.to_string())?;
+
+    Ok(Icon {
+        bytes,
+        etag,
+        modified
+    })
+}
+
+/// whether `icon` is a plain file name of an existing file in `ICONS_DIR`
+pub fn exists(icon: &str) -> bool {
+    let mut components = Path::new(icon).components();
+
+    matches!((components.next(), components.next()), (Some(Component::Normal(_)), None))
+        && Path::new(ICONS_DIR.as_str()).join(icon).is_file()
+}
+
+/// `icon` as `size`x`size` PNG, converted once per source file change
+pub fn get_icon(icon: &str, size: u32) -> Result<Icon, String> {
+    let path = Path::new(ICONS_DIR.as_str()).join(icon);
+    let modified = path.metadata()
+        .and_then(|m| m.modified())
+        .map_err(|e| format!("could not read {}: {e}", path.display()))?;
+    let key = (icon.to_string(), size);
+
+    if let Some(cached) = ICONS.lock().map_err(|e| e.to_string())?.get(&key)
+        && cached.modified == modified {
+        return Ok(cached.clone());
+    }
+
+    let converted = convert(&path, size, modified)?;
+
+    ICONS.lock().map_err(|e| e.to_string())?.insert(key, converted.clone());
+
+    Ok(converted)
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{GenericImageView, Rgba};
+
+    use super::*;
+
+    #[test]
+    fn convert_pads_to_a_square() {
+        let path = std::env::temp_dir().join(format!("lan-manager-icon-{}.png", std::process::id()));
+
+        RgbaImage::from_pixel(100, 50, Rgba([255, 0, 0, 255])).save(&path).unwrap();
+
+        let icon = convert(&path, 64, SystemTime::UNIX_EPOCH);
+        let _ = std::fs::remove_file(&path);
+        let image = image::load_from_memory(&icon.unwrap().bytes).unwrap();
+
+        assert_eq!(image.dimensions(), (64, 64));
+        assert_eq!(image.get_pixel(0, 0), Rgba([0, 0, 0, 0]));
+        assert_eq!(image.get_pixel(32, 32), Rgba([255, 0, 0, 255]));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,196 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Days, Local, NaiveTime, TimeDelta};
+use common::response::{history::Session, leaderboards::{Achievement, Leaderboard, LeaderboardEntry, LeaderboardKind, LeaderboardUnit}, now_playing::Player};
+
+use crate::{api::SharedData, repo::export::{merge, Interval}};
+
+const LEADERBOARD_SIZE: usize = 10;
+const NIGHT_END: NaiveTime = NaiveTime::from_hms_opt(6, 0, 0).unwrap();
+
+#[derive(Default)]
+struct PlayerStats {
+    /// games running at once count once
+    total: TimeDelta,
+    games: HashSet<String>,
+    longest_session: TimeDelta,
+    night: TimeDelta
+}
+
+struct AchievementDefinition {
+    /// stable, clients translate label and description by it
+    id: &'static str,
+    label: &'static str,
+    description: &'static str,
+    unlocked: fn(&PlayerStats) -> bool
+}
+
+const ACHIEVEMENTS: &[AchievementDefinition] = &[
+    AchievementDefinition {
+        id: "first_game",
+        label: "Angekommen",
+        description: "Das erste Spiel auf der LAN gestartet",
+        unlocked: |s| !s.games.is_empty()
+    },
+    AchievementDefinition {
+        id: "explorer",
+        label: "Entdecker",
+        description: "5 verschiedene Spiele gespielt",
+        unlocked: |s| s.games.len() >= 5
+    },
+    AchievementDefinition {
+        id: "marathon",
+        label: "Marathon",
+        description: "3 Stunden am Stück dasselbe Spiel gespielt",
+        unlocked: |s| s.longest_session >= TimeDelta::hours(3)
+    },
+    AchievementDefinition {
+        id: "night_owl",
+        label: "Nachteule",
+        description: "1 Stunde zwischen 0 und 6 Uhr gespielt",
+        unlocked: |s| s.night >= TimeDelta::hours(1)
+    },
+    AchievementDefinition {
+        id: "veteran",
+        label: "Veteran",
+        description: "Insgesamt 12 Stunden gespielt",
+        unlocked: |s| s.total >= TimeDelta::hours(12)
+    }
+];
+
+fn night_overlap(start: DateTime<Local>, end: DateTime<Local>) -> TimeDelta {
+    let mut overlap = TimeDelta::zero();
+    let mut day = start.date_naive();
+
+    while day <= end.date_naive() {
+        let night_start = day.and_time(NaiveTime::MIN).and_local_timezone(Local).earliest();
+        let night_end = day.and_time(NIGHT_END).and_local_timezone(Local).latest();
+
+        if let (Some(night_start), Some(night_end)) = (night_start, night_end) {
+            let from = start.max(night_start);
+            let to = end.min(night_end);
+
+            if to > from {
+                overlap += to - from;
+            }
+        }
+
+        let Some(next) = day.checked_add_days(Days::new(1)) else {
+            break;
+        };
+
+        day = next;
+    }
+
+    overlap
+}
+
+fn collect_stats<'a>(sessions: impl IntoIterator<Item = &'a Session>) -> HashMap<Player, PlayerStats> {
+    let now = Local::now();
+    let mut stats: HashMap<Player, PlayerStats> = HashMap::new();
+    let mut intervals: HashMap<Player, Vec<Interval>> = HashMap::new();
+
+    for session in sessions {
+        let end = session.end.unwrap_or(now);
+        let duration = end - session.start;
+        let by_player = stats.entry(session.player.clone()).or_default();
+
+        by_player.games.insert(session.game.name.clone());
+        by_player.longest_session = by_player.longest_session.max(duration);
+        intervals.entry(session.player.clone()).or_default().push((session.start, end));
+    }
+
+    for (player, intervals) in intervals {
+        let Some(by_player) = stats.get_mut(&player) else {
+            continue;
+        };
+
+        for (start, end) in merge(intervals) {
+            by_player.total += end - start;
+            by_player.night += night_overlap(start, end);
+        }
+    }
+
+    stats
+}
+
+fn leaderboard(stats: &HashMap<Player, PlayerStats>, kind: LeaderboardKind, label: &str, unit: LeaderboardUnit, value: fn(&PlayerStats) -> f64) -> Leaderboard {
+    let mut entries: Vec<LeaderboardEntry> = stats.iter()
+        .map(|(player, s)| LeaderboardEntry {
+            player: player.clone(),
+            value: value(s)
+        })
+        .filter(|e| e.value > 0.0)
+        .collect();
+
+    entries.sort_by(|a, b| b.value.total_cmp(&a.value).then_with(|| a.player.name.cmp(&b.player.name)));
+    entries.truncate(LEADERBOARD_SIZE);
+
+    Leaderboard {
+        kind,
+        label: label.to_string(),
+        unit,
+        entries
+    }
+}
+
+pub async fn get_leaderboards(data: &SharedData) -> Vec<Leaderboard> {
+    let stats = collect_stats(data.history.lock().await.sessions());
+
+    vec![
+        leaderboard(&stats, LeaderboardKind::MostHours, "Meiste Spielzeit", LeaderboardUnit::Seconds, |s| s.total.as_seconds_f64()),
+        leaderboard(&stats, LeaderboardKind::MostGames, "Meiste Spiele", LeaderboardUnit::Count, |s| s.games.len() as f64),
+        leaderboard(&stats, LeaderboardKind::LongestSession, "Längste Session", LeaderboardUnit::Seconds, |s| s.longest_session.as_seconds_f64()),
+        leaderboard(&stats, LeaderboardKind::NightOwl, "Nachteule", LeaderboardUnit::Seconds, |s| s.night.as_seconds_f64())
+    ]
+}
+
+pub async fn get_achievements(data: &SharedData, player: &str) -> Vec<Achievement> {
+    let stats = collect_stats(data.history.lock().await.sessions().filter(|s| s.player.id == player))
+        .into_values()
+        .next()
+        .unwrap_or_default();
+
+    ACHIEVEMENTS.iter()
+        .map(|a| Achievement {
+            id: a.id.to_string(),
+            label: a.label.to_string(),
+            description: a.description.to_string(),
+            unlocked: (a.unlocked)(&stats)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use common::game::Game;
+
+    use super::*;
+
+    fn session(game: &str, start: i64, end: i64) -> Session {
+        Session {
+            player: Player {
+                id: "1".to_string(),
+                name: "Alice".to_string()
+            },
+            game: Game {
+                name: game.to_string(),
+                label: game.to_string(),
+                icon: String::new()
+            },
+            start: DateTime::from_timestamp(start * 60, 0).unwrap().with_timezone(&Local),
+            end: DateTime::from_timestamp(end * 60, 0).map(|end| end.with_timezone(&Local))
+        }
+    }
+
+    #[test]
+    fn parallel_games_count_once() {
+        let sessions = [session("a", 0, 60), session("b", 30, 90)];
+        let stats = collect_stats(&sessions);
+        let stats = stats.values().next().unwrap();
+
+        assert_eq!(stats.total, TimeDelta::minutes(90));
+        assert_eq!(stats.longest_session, TimeDelta::minutes(60));
+        assert_eq!(stats.games.len(), 2);
+    }
+}
\ No newline at end of file
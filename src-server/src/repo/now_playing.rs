@@ -1,19 +1,103 @@
 use std::{collections::HashMap};
 use chrono::{DateTime, Local, TimeDelta};
-use common::{response::now_playing::{NowPlayingEntry, NowPlayingResponse, PartyPlayingEntry}};
+use common::{game::Game, response::{now_playing::{NowPlayingEntry, NowPlayingResponse, PartyPlayingEntry, Player}, players::PlayerStatus}};
 
-use crate::{api::{ActixData, SharedData}, metrics, repo::games::get_game};
+use serde::{Deserialize, Serialize};
 
+use crate::{api::{ActixData, SharedData}, events::DomainEvent, repo::{games::get_game, history}};
+
+/// players without a heartbeat for this long are considered offline
+pub const EXPIRY: TimeDelta = TimeDelta::seconds(30);
+/// a majority needs at least this many players, otherwise every solo session would qualify
+const MIN_MAJORITY_PLAYERS: usize = 2;
+pub const MAX_NAME_LENGTH: usize = 32;
+pub const MAX_ID_LENGTH: usize = 64;
+/// process names as reported by the OS, with some room for long executable names
+pub const MAX_PROCESS_LENGTH: usize = 128;
+pub const MAX_LABEL_LENGTH: usize = 64;
+/// more games than anyone runs at once
+pub const MAX_GAMES: usize = 32;
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct NowPlayingInfo {
-    timestamp: DateTime<Local>,
-    entry: NowPlayingEntry
+    pub timestamp: DateTime<Local>,
+    pub entry: NowPlayingEntry,
+    version: Option<String>
+}
+
+/// control characters and invisible formatting like bidi overrides or zero-width spaces
+fn is_hidden(c: char) -> bool {
+    c.is_control() || matches!(c, '\u{200B}'..='\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2060}'..='\u{2069}' | '\u{FEFF}')
+}
+
+/// strips hidden characters and surplus whitespace from a display name,
+/// which must not be empty and at most `max` characters afterwards
+pub fn clean_name(field: &str, value: &str, max: usize) -> Result<String, String> {
+    let name = value
+        .chars()
+        .filter(|c| !is_hidden(*c))
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ");
+
+    if name.is_empty() {
+        return Err(format!("{field}: must not be empty"));
+    }
+
+    if name.chars().count() > max {
+        return Err(format!("{field}: at most {max} characters"));
+    }
+
+    Ok(name)
+}
+
+/// identifiers like player ids or process names are compared as sent, so they are rejected instead of cleaned
+pub fn check_identifier(field: &str, value: &str, max: usize) -> Result<(), String> {
+    if value.is_empty() || value.len() > max || value.chars().any(is_hidden) {
+        return Err(format!("{field}: 1 to {max} visible characters"));
+    }
+
+    Ok(())
+}
+
+pub fn sanitize_player(player: &mut Player) -> Result<(), String> {
+    check_identifier("player id", &player.id, MAX_ID_LENGTH)?;
+    player.name = clean_name("player name", &player.name, MAX_NAME_LENGTH)?;
+
+    Ok(())
+}
+
+/// strips hidden characters and surplus whitespace from the name, drops duplicate games
+/// and rejects entries that are still invalid afterwards
+pub fn sanitize(entry: &mut NowPlayingEntry) -> Result<(), String> {
+    let name = clean_name("player name", &entry.player.name, MAX_NAME_LENGTH)?;
+
+    check_identifier("player id", &entry.player.id, MAX_ID_LENGTH)?;
+
+    entry.games.sort();
+    entry.games.dedup();
+
+    if entry.games.len() > MAX_GAMES {
+        return Err(format!("games: at most {MAX_GAMES}"));
+    }
+
+    entry.player.name = name;
+
+    Ok(())
 }
 
 pub async fn get_list(data: ActixData) -> NowPlayingResponse {
     let mut all_games: HashMap<String, PartyPlayingEntry> = HashMap::new();
+    let mut private = vec![];
     let store_lock = data.store.lock().await;
 
     for info in store_lock.values() {
+        if info.entry.private {
+            private.push(info.entry.player.clone());
+            continue;
+        }
+
         info.entry.games.iter().for_each(|name| {
             let Some(game) = get_game(name) else {
                 return;
@@ -30,66 +114,244 @@ pub async fn get_list(data: ActixData) -> NowPlayingResponse {
 
     NowPlayingResponse {
         active: all_games.into_values().collect(),
-        online: store_lock.len()
+        online: store_lock.len(),
+        private
+    }
+}
+
+pub async fn get_players(data: &SharedData) -> Vec<PlayerStatus> {
+    let store_lock = data.store.lock().await;
+    let mut players: Vec<PlayerStatus> = store_lock.values()
+        .map(|info| PlayerStatus {
+            player: info.entry.player.clone(),
+            games: info.entry.games.iter()
+                .filter_map(|g| get_game(g))
+                .collect(),
+            last_seen: info.timestamp,
+            version: info.version.clone(),
+            private: info.entry.private
+        })
+        .collect();
+
+    players.sort_by(|a, b| a.player.name.cmp(&b.player.name));
+
+    players
+}
+
+/// what the party looked like when it was last checked
+#[derive(Default)]
+pub struct PartyState {
+    majority: Option<String>,
+    record: usize
+}
+
+fn publish_left(data: &SharedData, info: NowPlayingInfo) {
+    for game in info.entry.games.iter().filter_map(|g| get_game(g)) {
+        data.events.publish(DomainEvent::GameStopped { player: info.entry.player.clone(), game });
     }
+
+    data.events.publish(DomainEvent::PlayerLeft { player: info.entry.player });
 }
 
-pub async fn update(data: &ActixData, mut entry: NowPlayingEntry) {
+pub async fn update(data: &ActixData, mut entry: NowPlayingEntry, version: Option<String>) {
     let mut store_lock = data.store.lock().await;
 
+    // private players are online without any trace of what they play
+    if entry.private {
+        entry.games.clear();
+    }
+
     entry.games.sort();
 
     let info = NowPlayingInfo {
         timestamp: Local::now(),
-        entry: entry.clone()
+        entry: entry.clone(),
+        version
     };
 
     let mut is_update = false;
-
-    if let Some(item) = store_lock.get_mut(&info.entry.player.id) {
-        if item.entry.player.id != info.entry.player.id || !item.entry.games.iter().eq(info.entry.games.iter()) {
+    let previous = if let Some(item) = store_lock.get_mut(&info.entry.player.id) {
+        if item.entry.player.id != info.entry.player.id || item.entry.private != info.entry.private || !item.entry.games.iter().eq(info.entry.games.iter()) {
             is_update = true;
         }
-        
-        *item = info;
+
+        Some(std::mem::replace(item, info).entry.games)
     } else {
         store_lock.insert(info.entry.player.id.clone(), info);
 
         is_update = true;
-    }
+
+        None
+    };
 
     drop(store_lock);
 
+    if previous.is_none() {
+        data.events.publish(DomainEvent::PlayerJoined { player: entry.player.clone() });
+    }
+
+    let previous = previous.unwrap_or_default();
+
+    for game in previous.iter().filter(|g| !entry.games.contains(g)).filter_map(|g| get_game(g)) {
+        data.events.publish(DomainEvent::GameStopped { player: entry.player.clone(), game });
+    }
+
+    for game in entry.games.iter().filter(|g| !previous.contains(g)).filter_map(|g| get_game(g)) {
+        data.events.publish(DomainEvent::GameStarted { player: entry.player.clone(), game });
+    }
+
     if is_update {
         *data.last_update.lock().await = Local::now();
+        check_party(data).await;
     }
 
-    metrics::record_played_games(&data.metrics, entry.player, entry.games.iter()
-        .filter_map(|g| get_game(g).cloned())
-        .collect()).await;
+    let games: Vec<Game> = entry.games.iter()
+        .filter_map(|g| get_game(g))
+        .collect();
+
+    history::record(data, &entry.player, &games).await;
+    data.events.publish(DomainEvent::Heartbeat { player: entry.player, games });
+}
+
+pub async fn remove(data: &SharedData, player: &String) -> bool {
+    let Some(info) = data.store.lock().await.remove(player) else {
+        return false;
+    };
+
+    history::close(data, player).await;
+    publish_left(data, info);
+    *data.last_update.lock().await = Local::now();
+    check_party(data).await;
+
+    true
 }
 
 pub async fn clean(data: SharedData) {
     let mut store_lock = data.store.lock().await;
-    let mut is_update = false;
     let mut expired: Vec<String> = vec![];
 
     for (player, info) in store_lock.iter() {
-        if info.timestamp < Local::now() - TimeDelta::seconds(30) {
+        if info.timestamp < Local::now() - EXPIRY {
             expired.push(player.clone());
-            is_update = true;
             continue;
         }
     }
 
+    let is_update = !expired.is_empty();
+
     for player in expired {
-        store_lock.remove(&player);
-        metrics::record_expired_player(&data.metrics, &player).await;
+        let Some(info) = store_lock.remove(&player) else {
+            continue;
+        };
+
+        history::close(&data, &player).await;
+        publish_left(&data, info);
     };
 
     drop(store_lock);
 
     if is_update {
         *data.last_update.lock().await = Local::now();
+        check_party(&data).await;
+    }
+}
+
+/// the game most players play, if at least half of `online` and `MIN_MAJORITY_PLAYERS` play it
+pub fn majority<K: Ord>(counts: HashMap<K, usize>, online: usize) -> Option<(K, usize)> {
+    counts.into_iter()
+        .filter(|(_, players)| *players >= MIN_MAJORITY_PLAYERS && *players >= online / 2)
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+}
+
+/// compares the party against its last check and publishes majority changes and online records
+pub async fn check_party(data: &SharedData) {
+    let store_lock = data.store.lock().await;
+    let online = store_lock.len();
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+
+    for info in store_lock.values() {
+        for game in &info.entry.games {
+            *counts.entry(game.as_str()).or_default() += 1;
+        }
+    }
+
+    let majority = majority(counts, online)
+        .and_then(|(name, players)| get_game(name).map(|game| (game, players)));
+
+    drop(store_lock);
+
+    let mut party_lock = data.party.lock().await;
+
+    if online > party_lock.record {
+        let is_first = party_lock.record == 0;
+
+        party_lock.record = online;
+
+        // the first player is not much of a record
+        if !is_first && online > 1 {
+            data.events.publish(DomainEvent::OnlineRecord { online });
+        }
+    }
+
+    let name = majority.as_ref().map(|(game, _)| game.name.clone());
+
+    if party_lock.majority == name {
+        return;
+    }
+
+    party_lock.majority = name;
+
+    let (game, players) = majority.map_or((None, 0), |(game, players)| (Some(game), players));
+
+    data.events.publish(DomainEvent::MajorityChanged { game, players, online });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, name: &str, games: &[&str]) -> NowPlayingEntry {
+        NowPlayingEntry {
+            player: Player {
+                id: id.to_string(),
+                name: name.to_string()
+            },
+            games: games.iter().map(|g| g.to_string()).collect(),
+            private: false
+        }
+    }
+
+    #[test]
+    fn sanitize_strips_hidden_characters_and_whitespace() {
+        let mut valid = entry("1", "  Al\u{200B}ice \u{202E}the   Great\t", &["b", "a", "b"]);
+
+        sanitize(&mut valid).unwrap();
+
+        assert_eq!(valid.player.name, "Alice the Great");
+        assert_eq!(valid.games, ["a", "b"]);
+    }
+
+    #[test]
+    fn sanitize_rejects_empty_names() {
+        assert!(sanitize(&mut entry("1", "", &[])).is_err());
+        assert!(sanitize(&mut entry("1", " \u{200B}\u{FEFF}\t", &[])).is_err());
+    }
+
+    #[test]
+    fn sanitize_rejects_over_length() {
+        assert!(sanitize(&mut entry("1", &"ä".repeat(MAX_NAME_LENGTH), &[])).is_ok());
+        assert!(sanitize(&mut entry("1", &"ä".repeat(MAX_NAME_LENGTH + 1), &[])).is_err());
+        assert!(sanitize(&mut entry(&"x".repeat(MAX_ID_LENGTH + 1), "Alice", &[])).is_err());
+
+        let games: Vec<String> = (0..=MAX_GAMES).map(|i| i.to_string()).collect();
+
+        assert!(sanitize(&mut entry("1", "Alice", &games.iter().map(String::as_str).collect::<Vec<_>>())).is_err());
+    }
+
+    #[test]
+    fn sanitize_rejects_hidden_characters_in_ids() {
+        assert!(sanitize(&mut entry("", "Alice", &[])).is_err());
+        assert!(sanitize(&mut entry("a\u{0}b", "Alice", &[])).is_err());
+        assert!(sanitize(&mut entry("a\u{200B}b", "Alice", &[])).is_err());
     }
 }
\ No newline at end of file
@@ -0,0 +1,358 @@
+use std::{collections::HashMap, fmt::Write as _};
+
+use chrono::{DateTime, FixedOffset, Local, TimeDelta};
+use common::{game::Game, response::{export::GameTotal, history::Session, now_playing::Player}};
+
+use crate::{api::SharedData, repo::{export::{clipped, get_export, playtime}, now_playing::majority, timeline::get_timeline}};
+
+/// games listed as most played
+const TOP_GAMES: usize = 5;
+
+pub struct PlayerHighlight {
+    pub player: Player,
+    pub total: TimeDelta,
+    pub favorite: Option<(Game, TimeDelta)>,
+    pub longest: Option<(Game, TimeDelta)>,
+    pub games: usize
+}
+
+pub struct MajorityPeriod {
+    pub game: Game,
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>
+}
+
+pub struct Recap {
+    pub from: Option<DateTime<Local>>,
+    pub to: Option<DateTime<Local>>,
+    pub total: TimeDelta,
+    pub games: Vec<GameTotal>,
+    /// most players online at once and when that was first reached
+    pub peak: Option<(usize, DateTime<Local>)>,
+    pub players: Vec<PlayerHighlight>,
+    pub majority: Vec<MajorityPeriod>
+}
+
+fn highlight(player: Player, sessions: &[&Session], from: Option<DateTime<Local>>, to: Option<DateTime<Local>>) -> PlayerHighlight {
+    let mut by_game: HashMap<&str, (&Game, TimeDelta)> = HashMap::new();
+    let mut longest: Option<(Game, TimeDelta)> = None;
+
+    for session in sessions {
+        let time = clipped(session, from, to);
+
+        by_game.entry(&session.game.name).or_insert((&session.game, TimeDelta::zero())).1 += time;
+
+        if longest.as_ref().is_none_or(|(_, longest)| time > *longest) {
+            longest = Some((session.game.clone(), time));
+        }
+    }
+
+    let favorite = by_game.values()
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.label.cmp(&a.0.label)))
+        .map(|(game, time)| ((*game).clone(), *time));
+
+    PlayerHighlight {
+        player,
+        total: playtime(sessions.iter().copied(), from, to),
+        favorite,
+        longest,
+        games: by_game.len()
+    }
+}
+
+/// most players online at once and when that was first reached, swept over session starts and ends,
+/// buckets would count two players handing over within one bucket as playing together
+fn peak(sessions: &[Session], from: Option<DateTime<Local>>, to: Option<DateTime<Local>>, now: DateTime<Local>) -> Option<(usize, DateTime<Local>)> {
+    // `false` sorts ends before starts at the same instant
+    let mut events: Vec<(DateTime<Local>, bool, &str)> = vec![];
+    let mut running: HashMap<&str, usize> = HashMap::new();
+    let mut peak: Option<(usize, DateTime<Local>)> = None;
+
+    for session in sessions {
+        let start = from.map_or(session.start, |from| session.start.max(from));
+        let end = session.end.unwrap_or(now);
+        let end = to.map_or(end, |to| end.min(to));
+
+        if end > start {
+            events.push((start, true, &session.player.id));
+            events.push((end, false, &session.player.id));
+        }
+    }
+
+    events.sort_by_key(|(time, is_start, _)| (*time, *is_start));
+
+    for (time, is_start, player) in events {
+        if is_start {
+            *running.entry(player).or_default() += 1;
+
+            if peak.is_none_or(|(players, _)| running.len() > players) {
+                peak = Some((running.len(), time));
+            }
+        } else if let Some(count) = running.get_mut(player) {
+            *count -= 1;
+
+            if *count == 0 {
+                running.remove(player);
+            }
+        }
+    }
+
+    peak
+}
+
+pub async fn get_recap(data: &SharedData, from: Option<DateTime<FixedOffset>>, to: Option<DateTime<FixedOffset>>) -> Result<Recap, String> {
+    let export = get_export(data, from, to).await;
+    let timeline = get_timeline(data, from, to, None).await?;
+    let step = TimeDelta::seconds(timeline.step);
+    let (from, to) = (export.from, export.to);
+    let peak = peak(&export.sessions, from, to, Local::now());
+    let mut periods: Vec<MajorityPeriod> = vec![];
+
+    for bucket in timeline.buckets {
+        let counts: HashMap<&str, usize> = bucket.games.iter()
+            .map(|entry| (entry.game.name.as_str(), entry.players))
+            .collect();
+        let Some((name, _)) = majority(counts, bucket.online) else {
+            continue;
+        };
+        let end = bucket.start + step;
+
+        match periods.last_mut() {
+            Some(last) if last.game.name == name && last.end == bucket.start => last.end = end,
+            _ => if let Some(entry) = bucket.games.iter().find(|e| e.game.name == name) {
+                periods.push(MajorityPeriod {
+                    game: entry.game.clone(),
+                    start: bucket.start,
+                    end
+                });
+            }
+        }
+    }
+
+    let mut by_player: HashMap<Player, Vec<&Session>> = HashMap::new();
+
+    for session in &export.sessions {
+        by_player.entry(session.player.clone()).or_default().push(session);
+    }
+
+    let mut players: Vec<PlayerHighlight> = by_player.into_iter()
+        .map(|(player, sessions)| highlight(player, &sessions, from, to))
+        .collect();
+
+    players.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.player.name.cmp(&b.player.name)));
+
+    Ok(Recap {
+        from,
+        to,
+        total: players.iter().map(|p| p.total).sum(),
+        games: export.games.into_iter().take(TOP_GAMES).collect(),
+        peak,
+        players,
+        majority: periods
+    })
+}
+
+fn duration(time: TimeDelta) -> String {
+    let minutes = time.num_minutes();
+
+    if minutes < 60 {
+        return format!("{minutes} min");
+    }
+
+    format!("{} h {:02} min", minutes / 60, minutes % 60)
+}
+
+fn timestamp(time: DateTime<Local>) -> String {
+    time.format("%d.%m. %H:%M").to_string()
+}
+
+fn range(recap: &Recap) -> String {
+    match (recap.from, recap.to) {
+        (Some(from), Some(to)) => format!("{} bis {}", timestamp(from), timestamp(to)),
+        (Some(from), None) => format!("seit {}", timestamp(from)),
+        (None, Some(to)) => format!("bis {}", timestamp(to)),
+        (None, None) => "gesamte Party".to_string()
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// `|` would end a table cell
+fn escape_markdown(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace('*', "\\*")
+        .replace('_', "\\_")
+}
+
+fn highlight_text(highlight: &PlayerHighlight, escape: fn(&str) -> String) -> String {
+    let mut parts = vec![format!("{} gespielt", duration(highlight.total)), format!("{} Spiel(e)", highlight.games)];
+
+    if let Some((game, time)) = &highlight.favorite {
+        parts.push(format!("am liebsten {} ({})", escape(&game.label), duration(*time)));
+    }
+
+    if let Some((game, time)) = &highlight.longest {
+        parts.push(format!("längste Session {} in {}", duration(*time), escape(&game.label)));
+    }
+
+    parts.join(", ")
+}
+
+pub fn to_markdown(recap: &Recap) -> String {
+    let mut out = String::new();
+
+    let _ = writeln!(out, "# LAN-Rückblick\n\n{}\n", range(recap));
+    let _ = writeln!(out, "- Gesamte Spielzeit: {}", duration(recap.total));
+
+    if let Some((players, at)) = recap.peak {
+        let _ = writeln!(out, "- Meiste Spieler gleichzeitig: {players} ({})", timestamp(at));
+    }
+
+    if !recap.games.is_empty() {
+        let _ = writeln!(out, "\n## Meistgespielte Spiele\n\n| Spiel | Spielzeit | Spieler |\n| --- | --- | --- |");
+
+        for total in &recap.games {
+            let _ = writeln!(out, "| {} | {} | {} |", escape_markdown(&total.game.label), duration(TimeDelta::seconds(total.seconds as i64)), total.players);
+        }
+    }
+
+    if !recap.players.is_empty() {
+        let _ = writeln!(out, "\n## Spieler\n");
+
+        for highlight in &recap.players {
+            let _ = writeln!(out, "- **{}**: {}", escape_markdown(&highlight.player.name), highlight_text(highlight, escape_markdown));
+        }
+    }
+
+    if !recap.majority.is_empty() {
+        let _ = writeln!(out, "\n## Was die Mehrheit gespielt hat\n");
+
+        for period in &recap.majority {
+            let _ = writeln!(out, "- {} – {}: {}", timestamp(period.start), period.end.format("%H:%M"), escape_markdown(&period.game.label));
+        }
+    }
+
+    out
+}
+
+pub fn to_html(recap: &Recap) -> String {
+    let mut out = String::new();
+
+    let _ = write!(out, "<!DOCTYPE html>\n<html lang=\"de\">\n<head>\n<meta charset=\"utf-8\">\n<title>LAN-Rückblick</title>\n<style>\
+        body {{ font-family: sans-serif; max-width: 48rem; margin: 2rem auto; padding: 0 1rem; color: #222; }}\
+        table {{ border-collapse: collapse; }} td, th {{ padding: .25rem .75rem; text-align: left; border-bottom: 1px solid #ddd; }}\
+        .timeline {{ display: flex; height: 1.5rem; border-radius: .25rem; overflow: hidden; background: #eee; }}\
+        .timeline div {{ background: hsl(var(--hue), 60%, 55%); border-right: 1px solid #fff; }}\
+        </style>\n</head>\n<body>\n");
+    let _ = write!(out, "<h1>LAN-Rückblick</h1>\n<p>{}</p>\n<ul>\n<li>Gesamte Spielzeit: {}</li>\n", range(recap), duration(recap.total));
+
+    if let Some((players, at)) = recap.peak {
+        let _ = writeln!(out, "<li>Meiste Spieler gleichzeitig: {players} ({})</li>", timestamp(at));
+    }
+
+    out.push_str("</ul>\n");
+
+    if !recap.games.is_empty() {
+        out.push_str("<h2>Meistgespielte Spiele</h2>\n<table>\n<tr><th>Spiel</th><th>Spielzeit</th><th>Spieler</th></tr>\n");
+
+        for total in &recap.games {
+            let _ = writeln!(out, "<tr><td>{}</td><td>{}</td><td>{}</td></tr>", escape(&total.game.label), duration(TimeDelta::seconds(total.seconds as i64)), total.players);
+        }
+
+        out.push_str("</table>\n");
+    }
+
+    if !recap.players.is_empty() {
+        out.push_str("<h2>Spieler</h2>\n<ul>\n");
+
+        for highlight in &recap.players {
+            let _ = writeln!(out, "<li><strong>{}</strong>: {}</li>", escape(&highlight.player.name), highlight_text(highlight, escape));
+        }
+
+        out.push_str("</ul>\n");
+    }
+
+    if let (Some(first), Some(last)) = (recap.majority.first(), recap.majority.last()) {
+        let span = (last.end - first.start).num_seconds().max(1) as f64;
+        let mut hues: HashMap<&str, usize> = HashMap::new();
+
+        out.push_str("<h2>Was die Mehrheit gespielt hat</h2>\n<div class=\"timeline\">\n");
+
+        for (i, period) in recap.majority.iter().enumerate() {
+            let next_hue = hues.len() * 67 % 360;
+            let hue = *hues.entry(&period.game.name).or_insert(next_hue);
+            // gaps without a majority stay empty
+            let gap = i.checked_sub(1).and_then(|i| recap.majority.get(i)).map_or(0.0, |previous| (period.start - previous.end).num_seconds() as f64 / span * 100.0);
+            let width = (period.end - period.start).num_seconds() as f64 / span * 100.0;
+
+            let _ = writeln!(out, "<div style=\"margin-left: {gap:.2}%; width: {width:.2}%; --hue: {hue}\" title=\"{}\"></div>", escape(&period.game.label));
+        }
+
+        out.push_str("</div>\n<ul>\n");
+
+        for period in &recap.majority {
+            let _ = writeln!(out, "<li>{} – {}: {}</li>", timestamp(period.start), period.end.format("%H:%M"), escape(&period.game.label));
+        }
+
+        out.push_str("</ul>\n");
+    }
+
+    out.push_str("</body>\n</html>\n");
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(minutes: i64) -> DateTime<Local> {
+        DateTime::from_timestamp(minutes * 60, 0).unwrap().with_timezone(&Local)
+    }
+
+    fn session(player: &str, game: &str, start: i64, end: i64) -> Session {
+        Session {
+            player: Player {
+                id: player.to_string(),
+                name: player.to_string()
+            },
+            game: Game {
+                name: game.to_string(),
+                label: game.to_string(),
+                icon: String::new()
+            },
+            start: at(start),
+            end: Some(at(end))
+        }
+    }
+
+    #[test]
+    fn peak_ignores_handovers() {
+        let sessions = vec![
+            session("1", "a", 0, 2),
+            session("2", "a", 2, 4),
+            session("3", "a", 3, 6)
+        ];
+
+        assert_eq!(peak(&sessions, None, None, at(10)), Some((2, at(3))));
+    }
+
+    #[test]
+    fn peak_counts_players_not_sessions() {
+        let sessions = vec![
+            session("1", "a", 0, 10),
+            session("1", "b", 0, 10),
+            session("2", "a", 5, 10)
+        ];
+
+        assert_eq!(peak(&sessions, None, None, at(10)), Some((2, at(5))));
+        assert_eq!(peak(&sessions, None, Some(at(5)), at(10)), Some((1, at(0))));
+        assert_eq!(peak(&[], None, None, at(10)), None);
+    }
+}
\ No newline at end of file
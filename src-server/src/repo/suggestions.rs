@@ -0,0 +1,142 @@
+use common::{game::Game, response::suggestions::{GameSuggestion, SuggestedGame}};
+
+use crate::{api::SharedData, repo::{games::{add_game, get_game}, now_playing::{check_identifier, clean_name, sanitize_player, MAX_LABEL_LENGTH, MAX_PROCESS_LENGTH}}};
+
+/// pending suggestions, further games are rejected until an admin reviewed some
+const MAX_SUGGESTIONS: usize = 200;
+/// players listed per suggestion, later ones still update the label
+const MAX_SUGGESTED_BY: usize = 50;
+
+/// records a suggestion, games already in the catalogue are ignored
+pub async fn suggest(data: &SharedData, mut suggested: SuggestedGame) -> Result<(), String> {
+    sanitize_player(&mut suggested.player)?;
+    check_identifier("name", &suggested.name, MAX_PROCESS_LENGTH)?;
+
+    let label = clean_name("label", &suggested.label, MAX_LABEL_LENGTH)?;
+
+    if get_game(&suggested.name).is_some() {
+        return Ok(());
+    }
+
+    let mut suggestions_lock = data.suggestions.lock().await;
+
+    if let Some(suggestion) = suggestions_lock.iter_mut().find(|s| s.name == suggested.name) {
+        suggestion.label = label;
+
+        let full = suggestion.suggested_by.len() >= MAX_SUGGESTED_BY;
+
+        match suggestion.suggested_by.iter_mut().find(|p| p.id == suggested.player.id) {
+            Some(player) => player.name = suggested.player.name,
+            None if !full => suggestion.suggested_by.push(suggested.player),
+            None => {}
+        }
+
+        return Ok(());
+    }
+
+    if suggestions_lock.len() >= MAX_SUGGESTIONS {
+        return Err(format!("at most {MAX_SUGGESTIONS} suggestions can be pending"));
+    }
+
+    suggestions_lock.push(GameSuggestion {
+        name: suggested.name,
+        label,
+        suggested_by: vec![suggested.player]
+    });
+
+    Ok(())
+}
+
+pub async fn get_suggestions(data: &SharedData) -> Vec<GameSuggestion> {
+    data.suggestions.lock().await.clone()
+}
+
+pub async fn remove(data: &SharedData, name: &str) -> Option<GameSuggestion> {
+    let mut suggestions_lock = data.suggestions.lock().await;
+    let index = suggestions_lock.iter().position(|s| s.name == name)?;
+
+    Some(suggestions_lock.remove(index))
+}
+
+/// moves the suggestion into the catalogue, `icon` is a file name in `ICONS_DIR`
+pub async fn approve(data: &SharedData, name: &str, label: Option<String>, icon: String) -> Result<Option<Game>, String> {
+    let Some(suggestion) = remove(data, name).await else {
+        return Ok(None);
+    };
+    let game = Game {
+        name: suggestion.name.clone(),
+        label: label.unwrap_or(suggestion.label.clone()),
+        icon
+    };
+
+    if let Err(e) = add_game(game.clone()) {
+        data.suggestions.lock().await.push(suggestion);
+
+        return Err(e);
+    }
+
+    Ok(Some(game))
+}
+
+#[cfg(test)]
+mod tests {
+    use common::response::now_playing::Player;
+
+    use super::*;
+
+    fn suggested(player: &str, player_name: &str, name: &str) -> SuggestedGame {
+        SuggestedGame {
+            player: Player {
+                id: player.to_string(),
+                name: player_name.to_string()
+            },
+            name: name.to_string(),
+            label: " Some  Game\u{200B} ".to_string()
+        }
+    }
+
+    #[actix_web::test]
+    async fn suggest_dedupes_players_by_id() {
+        let data = SharedData::new();
+
+        suggest(&data, suggested("1", "Alice", "some-game")).await.unwrap();
+        suggest(&data, suggested("1", "Alicia", "some-game")).await.unwrap();
+        suggest(&data, suggested("2", "Bob", "some-game")).await.unwrap();
+
+        let suggestions = get_suggestions(&data).await;
+
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].label, "Some Game");
+        assert_eq!(suggestions[0].suggested_by.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), ["Alicia", "Bob"]);
+    }
+
+    #[actix_web::test]
+    async fn suggest_rejects_invalid_fields() {
+        let data = SharedData::new();
+
+        assert!(suggest(&data, suggested("1", "   ", "some-game")).await.is_err());
+        assert!(suggest(&data, suggested("1", "Alice", "some\u{202E}game")).await.is_err());
+        assert!(suggest(&data, suggested("1", "Alice", &"x".repeat(MAX_PROCESS_LENGTH + 1))).await.is_err());
+        assert!(get_suggestions(&data).await.is_empty());
+    }
+
+    #[actix_web::test]
+    async fn suggest_caps_pending_suggestions_and_players() {
+        let data = SharedData::new();
+
+        for i in 0..MAX_SUGGESTIONS {
+            suggest(&data, suggested("1", "Alice", &format!("game-{i}"))).await.unwrap();
+        }
+
+        for i in 0..MAX_SUGGESTED_BY + 5 {
+            suggest(&data, suggested(&i.to_string(), "Bob", "game-0")).await.unwrap();
+        }
+
+        assert!(suggest(&data, suggested("1", "Alice", "one-too-many")).await.is_err());
+
+        let suggestions = get_suggestions(&data).await;
+
+        assert_eq!(suggestions.len(), MAX_SUGGESTIONS);
+        assert_eq!(suggestions[0].suggested_by.len(), MAX_SUGGESTED_BY);
+    }
+}
\ No newline at end of file
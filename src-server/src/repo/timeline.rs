@@ -0,0 +1,175 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, FixedOffset, Local, TimeDelta};
+use common::{game::Game, response::{history::Session, timeline::{TimelineBucket, TimelineEntry, TimelineResponse}}};
+
+use crate::{api::SharedData, repo::history::get_sessions};
+
+pub const MIN_STEP: TimeDelta = TimeDelta::minutes(1);
+pub const MAX_STEP: TimeDelta = TimeDelta::days(365);
+const DEFAULT_STEP: TimeDelta = TimeDelta::minutes(5);
+/// keeps responses small, without an explicit step it grows to stay below this
+const MAX_BUCKETS: i64 = 2000;
+
+fn bucket(sessions: &[Session], start: DateTime<Local>, end: DateTime<Local>, now: DateTime<Local>) -> TimelineBucket {
+    let mut online: HashSet<&str> = HashSet::new();
+    let mut games: HashMap<&str, (&Game, HashSet<&str>)> = HashMap::new();
+
+    for session in sessions.iter().filter(|s| s.start < end && s.end.unwrap_or(now) > start) {
+        online.insert(&session.player.id);
+        games.entry(&session.game.name)
+            .or_insert((&session.game, HashSet::new()))
+            .1.insert(&session.player.id);
+    }
+
+    let mut games: Vec<TimelineEntry> = games.into_values()
+        .map(|(game, players)| TimelineEntry {
+            game: game.clone(),
+            players: players.len()
+        })
+        .collect();
+
+    games.sort_by(|a, b| b.players.cmp(&a.players).then_with(|| a.game.label.cmp(&b.game.label)));
+
+    TimelineBucket {
+        start,
+        online: online.len(),
+        games
+    }
+}
+
+/// the aligned start as a timestamp, the step and the number of buckets covering `from` to `to`,
+/// without an explicit step the smallest whole minute keeping the aligned span within `MAX_BUCKETS`
+fn layout(from: DateTime<Local>, to: DateTime<Local>, step: Option<TimeDelta>) -> Result<(i64, TimeDelta, i64), String> {
+    let step = match step {
+        Some(step) => step,
+        None => {
+            // aligning adds less than one step to the span, which the two spare buckets absorb
+            let minutes = (to - from).num_seconds() / (60 * (MAX_BUCKETS - 2)) + 1;
+
+            DEFAULT_STEP.max(TimeDelta::minutes(minutes))
+        }
+    };
+    let step_seconds = step.num_seconds();
+    let first = from.timestamp() - from.timestamp().rem_euclid(step_seconds);
+    let count = (to.timestamp() - first) / step_seconds + 1;
+
+    if count > MAX_BUCKETS {
+        return Err(format!("{count} buckets exceed the maximum of {MAX_BUCKETS}, increase the step"));
+    }
+
+    Ok((first, step, count))
+}
+
+/// splits `from` to `to` into buckets of `step`, aligned to multiples of `step`,
+/// `from` defaults to the first recorded session and `step` to a length fitting `MAX_BUCKETS`
+pub async fn get_timeline(data: &SharedData, from: Option<DateTime<FixedOffset>>, to: Option<DateTime<FixedOffset>>, step: Option<TimeDelta>) -> Result<TimelineResponse, String> {
+    let now = Local::now();
+    let sessions = get_sessions(data, from, to).await;
+    let to = to.map_or(now, |to| to.with_timezone(&Local));
+    let from = from.map(|from| from.with_timezone(&Local))
+        .or_else(|| sessions.iter().map(|s| s.start).min())
+        .unwrap_or(to);
+
+    if from > to {
+        return Err("from is after to".to_string());
+    }
+
+    let (first, step, count) = layout(from, to, step)?;
+    let step_seconds = step.num_seconds();
+    let buckets = (0..count)
+        .map_while(|i| first.checked_add(i.checked_mul(step_seconds)?))
+        .map_while(|start| DateTime::from_timestamp(start, 0))
+        .map(|start| start.with_timezone(&Local))
+        // the last bucket may end beyond what `DateTime` can represent
+        .map_while(|start| Some(bucket(&sessions, start, start.checked_add_signed(step)?, now)))
+        .collect();
+
+    Ok(TimelineResponse {
+        from,
+        to,
+        step: step_seconds,
+        buckets
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use common::response::now_playing::Player;
+
+    use super::*;
+
+    fn at(timestamp: i64) -> DateTime<Local> {
+        DateTime::from_timestamp(timestamp, 0).unwrap().with_timezone(&Local)
+    }
+
+    fn session(player: &str, game: &str, start: i64, end: i64) -> Session {
+        Session {
+            player: Player {
+                id: player.to_string(),
+                name: player.to_string()
+            },
+            game: Game {
+                name: game.to_string(),
+                label: game.to_string(),
+                icon: String::new()
+            },
+            start: at(start),
+            end: Some(at(end))
+        }
+    }
+
+    #[test]
+    fn buckets_are_aligned_to_the_step() {
+        let (first, step, count) = layout(at(1_000_130), at(1_000_730), Some(TimeDelta::minutes(5))).unwrap();
+
+        assert_eq!(first, 999_900);
+        assert_eq!(step, TimeDelta::minutes(5));
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn default_step_fits_misaligned_spans() {
+        // 4:59 past an aligned start and a span of 9999:59 needed 2001 buckets of the old default
+        let from = 1_000_200 + 299;
+        let to = from + 9999 * 60 + 59;
+
+        let (_, step, count) = layout(at(from), at(to), None).unwrap();
+
+        assert!(count <= MAX_BUCKETS);
+        assert_eq!(step, TimeDelta::minutes(6));
+
+        for span in [0, 59, 3600, 599_999, 6_000_000, 100_000_000] {
+            for offset in [0, 1, 299, 359, 3599] {
+                let from = 1_000_200 + offset;
+                let (_, _, count) = layout(at(from), at(from + span), None).unwrap();
+
+                assert!(count <= MAX_BUCKETS, "{count} buckets for span {span} offset {offset}");
+            }
+        }
+    }
+
+    #[test]
+    fn explicit_step_may_exceed_the_limit() {
+        assert!(layout(at(0), at(2000 * 60), Some(MIN_STEP)).is_err());
+        assert!(layout(at(0), at(1999 * 60), Some(MIN_STEP)).is_ok());
+    }
+
+    #[test]
+    fn bucket_counts_overlapping_sessions() {
+        let sessions = vec![
+            session("1", "a", 0, 600),
+            session("2", "a", 300, 900),
+            session("2", "b", 300, 900),
+            session("3", "b", 900, 1200)
+        ];
+
+        let late = bucket(&sessions, at(600), at(900), at(1200));
+        let early = bucket(&sessions, at(0), at(300), at(1200));
+
+        assert_eq!(late.online, 1);
+        assert_eq!(late.games.len(), 2);
+        assert_eq!(early.online, 1);
+        assert_eq!(early.games[0].game.name, "a");
+    }
+}
\ No newline at end of file
@@ -0,0 +1,168 @@
+use chrono::Local;
+use common::{game::Game, response::unknown_processes::{UnknownProcessStats, UnknownProcessesReport}};
+
+use crate::{api::SharedData, repo::{games::{add_game, get_game}, now_playing::{check_identifier, sanitize_player, MAX_PROCESS_LENGTH}}};
+
+/// processes beyond this are dropped from a single report
+const MAX_REPORTED: usize = 50;
+/// processes kept for review, new ones are dropped until an admin reviewed some
+const MAX_PROCESSES: usize = 500;
+/// players listed per process, enough to tell how widespread it is
+const MAX_PLAYERS: usize = 50;
+
+/// merges a player's report, processes already in the catalogue are ignored
+pub async fn report(data: &SharedData, mut report: UnknownProcessesReport) -> Result<(), String> {
+    sanitize_player(&mut report.player)?;
+    report.processes.truncate(MAX_REPORTED);
+
+    for process in &report.processes {
+        check_identifier("process name", &process.name, MAX_PROCESS_LENGTH)?;
+    }
+
+    let mut processes_lock = data.unknown_processes.lock().await;
+
+    for process in report.processes {
+        if get_game(&process.name).is_some() {
+            continue;
+        }
+
+        let index = match processes_lock.iter().position(|p| p.name == process.name) {
+            Some(index) => index,
+            None if processes_lock.len() >= MAX_PROCESSES => continue,
+            None => {
+                processes_lock.push(UnknownProcessStats {
+                    name: process.name,
+                    heuristics: vec![],
+                    players: vec![],
+                    last_seen: Local::now()
+                });
+
+                processes_lock.len() - 1
+            }
+        };
+        let stats = &mut processes_lock[index];
+
+        // `Heuristic` has few variants, so deduping bounds the list
+        for heuristic in process.heuristics {
+            if !stats.heuristics.contains(&heuristic) {
+                stats.heuristics.push(heuristic);
+            }
+        }
+
+        let full = stats.players.len() >= MAX_PLAYERS;
+
+        match stats.players.iter_mut().find(|p| p.id == report.player.id) {
+            Some(player) => player.name = report.player.name.clone(),
+            None if !full => stats.players.push(report.player.clone()),
+            None => {}
+        }
+
+        stats.last_seen = Local::now();
+    }
+
+    Ok(())
+}
+
+/// most widespread processes first
+pub async fn get_unknown_processes(data: &SharedData) -> Vec<UnknownProcessStats> {
+    let mut processes = data.unknown_processes.lock().await.clone();
+
+    processes.sort_by(|a, b| b.players.len().cmp(&a.players.len()).then_with(|| a.name.cmp(&b.name)));
+
+    processes
+}
+
+pub async fn remove(data: &SharedData, name: &str) -> Option<UnknownProcessStats> {
+    let mut processes_lock = data.unknown_processes.lock().await;
+    let index = processes_lock.iter().position(|p| p.name == name)?;
+
+    Some(processes_lock.remove(index))
+}
+
+/// moves the process into the catalogue, `icon` is a file name in `ICONS_DIR`
+pub async fn promote(data: &SharedData, name: &str, label: Option<String>, icon: String) -> Result<Option<Game>, String> {
+    let Some(stats) = remove(data, name).await else {
+        return Ok(None);
+    };
+    let default_label = stats.name.strip_suffix(".exe").unwrap_or(&stats.name).to_string();
+    let game = Game {
+        name: stats.name.clone(),
+        label: label.unwrap_or(default_label),
+        icon
+    };
+
+    if let Err(e) = add_game(game.clone()) {
+        data.unknown_processes.lock().await.push(stats);
+
+        return Err(e);
+    }
+
+    Ok(Some(game))
+}
+
+#[cfg(test)]
+mod tests {
+    use common::response::{now_playing::Player, unknown_processes::{Heuristic, UnknownProcess}};
+
+    use super::*;
+
+    fn reported(player: &str, processes: &[&str]) -> UnknownProcessesReport {
+        UnknownProcessesReport {
+            player: Player {
+                id: player.to_string(),
+                name: format!(" Player\u{200B} {player}")
+            },
+            processes: processes.iter()
+                .map(|name| UnknownProcess {
+                    name: name.to_string(),
+                    heuristics: vec![Heuristic::HighCpu, Heuristic::HighCpu, Heuristic::GpuHeavy]
+                })
+                .collect()
+        }
+    }
+
+    #[actix_web::test]
+    async fn report_merges_players_and_heuristics() {
+        let data = SharedData::new();
+
+        report(&data, reported("1", &["game.exe"])).await.unwrap();
+        report(&data, reported("1", &["game.exe"])).await.unwrap();
+        report(&data, reported("2", &["game.exe"])).await.unwrap();
+
+        let processes = get_unknown_processes(&data).await;
+
+        assert_eq!(processes.len(), 1);
+        assert_eq!(processes[0].heuristics, [Heuristic::HighCpu, Heuristic::GpuHeavy]);
+        assert_eq!(processes[0].players.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), ["Player 1", "Player 2"]);
+    }
+
+    #[actix_web::test]
+    async fn report_rejects_invalid_names() {
+        let data = SharedData::new();
+
+        assert!(report(&data, reported("", &["game.exe"])).await.is_err());
+        assert!(report(&data, reported("1", &["game.exe", "game\u{0}.exe"])).await.is_err());
+        assert!(report(&data, reported("1", &[""])).await.is_err());
+        assert!(get_unknown_processes(&data).await.is_empty());
+    }
+
+    #[actix_web::test]
+    async fn report_caps_processes_and_players() {
+        let data = SharedData::new();
+
+        for chunk in 0..MAX_PROCESSES / MAX_REPORTED + 1 {
+            let names: Vec<String> = (0..MAX_REPORTED).map(|i| format!("process-{chunk}-{i}.exe")).collect();
+
+            report(&data, reported("1", &names.iter().map(String::as_str).collect::<Vec<_>>())).await.unwrap();
+        }
+
+        for player in 0..MAX_PLAYERS + 5 {
+            report(&data, reported(&player.to_string(), &["process-0-0.exe"])).await.unwrap();
+        }
+
+        let processes = get_unknown_processes(&data).await;
+
+        assert_eq!(processes.len(), MAX_PROCESSES);
+        assert_eq!(processes[0].players.len(), MAX_PLAYERS);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,75 @@
+use std::{collections::HashMap, fs};
+
+use chrono::{DateTime, Local};
+use common::response::{history::Session, suggestions::GameSuggestion, unknown_processes::UnknownProcessStats};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::{api::SharedData, config::SNAPSHOT_FILE, metrics::{self, CounterSnapshot}, repo::{history::{self, History}, now_playing::{NowPlayingInfo, EXPIRY}}};
+
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    created: DateTime<Local>,
+    store: Vec<NowPlayingInfo>,
+    last_update: DateTime<Local>,
+    counters: Vec<CounterSnapshot>,
+    history: Vec<Session>,
+    #[serde(default)]
+    suggestions: Vec<GameSuggestion>,
+    #[serde(default)]
+    unknown_processes: Vec<UnknownProcessStats>
+}
+
+pub async fn save(data: &SharedData) -> Result<(), String> {
+    let snapshot = Snapshot {
+        created: Local::now(),
+        store: data.store.lock().await.values().cloned().collect(),
+        last_update: *data.last_update.lock().await,
+        counters: metrics::snapshot_counters(&data.metrics).await,
+        history: data.history.lock().await.to_vec(),
+        suggestions: data.suggestions.lock().await.clone(),
+        unknown_processes: data.unknown_processes.lock().await.clone()
+    };
+    let content = serde_json::to_string(&snapshot).map_err(|e| e.to_string())?;
+    let tmp_file = format!("{}.tmp", SNAPSHOT_FILE.as_str());
+
+    fs::write(&tmp_file, content).map_err(|e| format!("could not write {tmp_file}: {e}"))?;
+    fs::rename(&tmp_file, SNAPSHOT_FILE.as_str()).map_err(|e| format!("could not move {tmp_file}: {e}"))?;
+
+    info!("saved snapshot with {} player(s) and {} session(s)", snapshot.store.len(), snapshot.history.len());
+
+    Ok(())
+}
+
+pub async fn restore(data: &SharedData) -> Result<(), String> {
+    let content = match fs::read_to_string(SNAPSHOT_FILE.as_str()) {
+        Ok(content) => content,
+        Err(e) => {
+            warn!("no snapshot restored from {}: {e}", SNAPSHOT_FILE.as_str());
+
+            return Ok(());
+        }
+    };
+    let snapshot = serde_json::from_str::<Snapshot>(&content)
+        .map_err(|e| format!("could not parse snapshot: {e}"))?;
+    let (store, expired): (Vec<NowPlayingInfo>, Vec<NowPlayingInfo>) = snapshot.store.into_iter()
+        .partition(|info| info.timestamp >= Local::now() - EXPIRY);
+
+    info!("restoring snapshot from {} with {} player(s), discarding {} expired", snapshot.created, store.len(), expired.len());
+
+    *data.history.lock().await = History::from_sessions(snapshot.history);
+
+    for info in expired {
+        history::close_at(data, &info.entry.player.id, snapshot.created).await;
+    }
+
+    *data.store.lock().await = store.into_iter()
+        .map(|info| (info.entry.player.id.clone(), info))
+        .collect::<HashMap<String, NowPlayingInfo>>();
+    *data.last_update.lock().await = snapshot.last_update;
+    *data.suggestions.lock().await = snapshot.suggestions;
+    *data.unknown_processes.lock().await = snapshot.unknown_processes;
+    metrics::restore_counters(&data.metrics, snapshot.counters).await;
+
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,53 @@
+use std::{collections::HashSet, sync::LazyLock};
+
+use common::protocol::Capability;
+use log::{info, warn};
+use notify_rust::{Notification, Timeout};
+use tokio::sync::Mutex;
+
+use lan_core::{api::{get_achievements, get_info, ApiError}, i18n::{self, Message}};
+
+use crate::app::send_event;
+
+// `None` until the first poll, so achievements unlocked before startup don't notify
+static UNLOCKED: LazyLock<Mutex<Option<HashSet<String>>>> = LazyLock::new(|| Mutex::new(None));
+
+pub async fn poll() -> Result<(), ApiError> {
+    if !get_info().await?.supports(Capability::Achievements) {
+        return Ok(());
+    }
+
+    let achievements = get_achievements().await?.achievements;
+    let mut unlocked_lock = UNLOCKED.lock().await;
+    let Some(unlocked) = unlocked_lock.as_mut() else {
+        *unlocked_lock = Some(achievements.into_iter()
+            .filter(|a| a.unlocked)
+            .map(|a| a.id)
+            .collect());
+
+        return Ok(());
+    };
+
+    for achievement in achievements {
+        if !achievement.unlocked || !unlocked.insert(achievement.id.clone()) {
+            continue;
+        }
+
+        info!("unlocked achievement {}", achievement.id);
+        send_event("achievement_unlocked", &achievement).await
+            .map_err(ApiError::internal)?;
+
+        if let Err(e) = Notification::new()
+            .summary("LAN Manager")
+            .body(Message::AchievementUnlocked {
+                label: &achievement.label,
+                description: &achievement.description
+            }.translate(i18n::language()).as_str())
+            .timeout(Timeout::Milliseconds(10_000))
+            .show() {
+            warn!("failed to show notification: {e}");
+        }
+    }
+
+    Ok(())
+}
\ No newline at end of file
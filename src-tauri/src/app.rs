@@ -1,14 +1,26 @@
-use std::sync::{Arc, LazyLock};
+use std::{collections::HashSet, sync::{Arc, LazyLock}, thread};
 
-use common::{game::Game, response::now_playing::NowPlayingResponse};
-use log::warn;
-use tauri::{AppHandle, Manager};
+use common::{game::{CustomGame, Game}, protocol::Capability, response::{leaderboards::{Achievement, Leaderboard}, now_playing::NowPlayingResponse}};
+use lan_core::{api::{self, ErrorMessage}, config::{self, Privacy, Settings}, discovery, processes::{self, STATE}};
+use log::{info, warn};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, RunEvent};
 use tauri_plugin_autostart::ManagerExt;
 use tokio::sync::Mutex;
-use crate::{api, config::{self, Settings}, processes};
+
+use crate::tray;
 
 pub static APP_HANDLE: LazyLock<Arc<Mutex<Option<AppHandle>>>> = LazyLock::new(|| Arc::new(Mutex::new(None)));
 
+pub async fn send_event<T: Serialize>(event: &str, obj: &T) -> Result<(), String> {
+    let app_lock_opt = APP_HANDLE.lock().await;
+    let Some(app_lock) = app_lock_opt.as_ref() else {
+        return Err("app handle not set".to_string())
+    };
+
+    app_lock.emit(event, obj).map_err(|e| format!("could not emit event: {e}"))
+}
+
 #[tauri::command]
 fn set_config(app_handle: AppHandle, config: Settings) -> Result<(), String> {
     let old_config = config::get_or_create_config(true)?;
@@ -22,6 +34,35 @@ fn set_config(app_handle: AppHandle, config: Settings) -> Result<(), String> {
     }
 
     config::set_config(&config)?;
+
+    if !config.custom_games.is_empty() {
+        let previous = old_config.custom_games.clone();
+
+        // only games added or relabeled by this save, the others were suggested before
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = api::suggest_custom_games(&previous).await {
+                warn!("failed to suggest custom games: {e}");
+            }
+        });
+    }
+
+    // leave right away instead of waiting for the heartbeat to expire
+    if old_config.privacy != config.privacy && config.privacy == Privacy::Paused {
+        tauri::async_runtime::spawn(async {
+            if let Err(e) = api::delete_now_playing().await {
+                warn!("failed to leave the party: {e}");
+            }
+        });
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn set_privacy(privacy: Privacy) -> Result<(), String> {
+    processes::set_privacy(privacy).await?;
+    tray::update().await;
+
     Ok(())
 }
 
@@ -32,12 +73,53 @@ fn get_config() -> Result<Settings, String> {
 
 #[tauri::command]
 async fn get_now_playing() -> Option<NowPlayingResponse> {
-    processes::CTX.lock().await.last_response.clone()
+    STATE.lock().await.last_response.clone()
 }
 
 #[tauri::command]
-async fn get_games() -> Result<Vec<Game>, String> {
-    api::get_games().await.map(|g| g.games)
+async fn get_games() -> Result<Vec<Game>, ErrorMessage> {
+    Ok(api::get_catalogue().await?)
+}
+
+#[tauri::command]
+async fn get_leaderboards() -> Result<Vec<Leaderboard>, ErrorMessage> {
+    Ok(api::get_leaderboards().await?.leaderboards)
+}
+
+#[tauri::command]
+async fn get_achievements() -> Result<Vec<Achievement>, ErrorMessage> {
+    Ok(api::get_achievements().await?.achievements)
+}
+
+/// installed games that are neither in the catalogue nor custom games yet
+#[tauri::command]
+async fn discover_games() -> Result<Vec<CustomGame>, String> {
+    let known = api::get_catalogue().await
+        .map(|games| games.into_iter().map(|g| g.name).collect::<HashSet<_>>())
+        .unwrap_or_default();
+
+    tauri::async_runtime::spawn_blocking(move || discovery::discover(&known))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// tells the server we are gone instead of waiting for the heartbeat to expire
+fn leave() {
+    // the event loop blocks the main thread, so the request is driven from a helper thread
+    let handle = tokio::runtime::Handle::current();
+    let res = thread::spawn(move || handle.block_on(async {
+        if !api::get_info().await?.supports(Capability::Leave) {
+            return Ok(());
+        }
+
+        api::delete_now_playing().await
+    })).join();
+
+    match res {
+        Ok(Ok(())) => info!("left the party"),
+        Ok(Err(e)) => warn!("failed to leave the party: {e}"),
+        Err(_) => warn!("failed to leave the party: request panicked")
+    }
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -66,6 +148,8 @@ pub fn run() {
                 }
             }
 
+            tray::create(app.handle())?;
+
             let handle_clone = app.handle().clone();
 
             tauri::async_runtime::spawn(async move {
@@ -74,7 +158,12 @@ pub fn run() {
 
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![get_config, set_config, get_now_playing, get_games])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .invoke_handler(tauri::generate_handler![get_config, set_config, set_privacy, get_now_playing, get_games, get_leaderboards, get_achievements, discover_games])
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|_app, event| {
+            if let RunEvent::Exit = event {
+                leave();
+            }
+        });
 }
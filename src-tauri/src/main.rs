@@ -4,14 +4,15 @@
 use std::{process::exit, time::Duration};
 
 use clokwerk::{AsyncScheduler, TimeUnits};
-use log::error;
+use lan_core::{api::ErrorMessage, telemetry};
+use log::{error, warn};
 
-use crate::api::send_event;
+use crate::app::send_event;
 
 mod app;
-mod config;
 mod processes;
-mod api;
+mod achievements;
+mod tray;
 
 #[tokio::main]
 async fn main() {
@@ -24,11 +25,31 @@ async fn main() {
     let mut scheduler = AsyncScheduler::new();
 
     scheduler.every(5.seconds()).run(async || {
-        let res = processes::poll().await.err();
+        let res = processes::poll().await.err().map(ErrorMessage::from);
 
         if let Err(e) = send_event("poll_error", &res).await {
             error!("failed to send event: {e}");
         }
+
+        tray::update().await;
+    });
+
+    scheduler.every(1.minutes()).run(async || {
+        if let Err(e) = processes::refresh_games().await {
+            warn!("failed to refresh games: {e}");
+        }
+    });
+
+    scheduler.every(1.minutes()).run(async || {
+        if let Err(e) = achievements::poll().await {
+            warn!("failed to poll achievements: {e}");
+        }
+    });
+
+    scheduler.every(5.minutes()).run(async || {
+        if let Err(e) = telemetry::poll().await {
+            warn!("failed to report unknown processes: {e}");
+        }
     });
 
     tokio::spawn(async move {
@@ -1,29 +1,14 @@
-use std::{collections::HashSet, sync::LazyLock};
+use std::sync::LazyLock;
 
-use chrono::{DateTime, Local, TimeDelta};
-use common::{game::Game, response::now_playing::NowPlayingResponse};
+use common::game::Game;
+use lan_core::{api::{self, get_catalogue, get_icon, ApiError}, i18n::{self, Message}, processes::{self, STATE}};
 use log::{info, warn};
 use notify_rust::{Notification, Timeout};
-use serde::Serialize;
-use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
 use tokio::sync::Mutex;
 
-use crate::api::{get_games, get_icon, put_now_playing, send_event};
-const GRACE_PERIOD: u16 = 4;
-
-#[derive(Serialize, Clone)]
-struct Process {
-    name: String,
-    exe: Option<String>,
-    cmd: Vec<String>,
-    cwd: Option<String>
-}
+use crate::app::send_event;
 
-pub struct ProcessContext {
-    pub last_response: Option<NowPlayingResponse>,
-    pub last_put: DateTime<Local>,
-    pub notification_ctx: Option<NotificationContext>
-}
+const GRACE_PERIOD: u16 = 4;
 
 pub struct NotificationContext {
     active_game: Game,
@@ -55,109 +40,49 @@ impl NotificationContext {
     }
 }
 
-pub static CTX: LazyLock<Mutex<ProcessContext>> = LazyLock::new(|| Mutex::new(ProcessContext {
-    last_put: Local::now() - TimeDelta::days(300),
-    last_response: None,
-    notification_ctx: None
-}));
-
-fn get_processes() -> Vec<Process> {
-    let mut system = System::new();
-    system.refresh_processes_specifics(
-        ProcessesToUpdate::All, 
-        true,
-        ProcessRefreshKind::nothing()
-            .with_exe(UpdateKind::Always)
-            .with_cwd(UpdateKind::Always)
-            .with_cmd(UpdateKind::Always)
-    );
-
-    system.processes()
-        .values()
-        .filter_map(|p| p.name().to_str().map(|name| Process {
-                name: String::from(name),
-                exe: p.exe()
-                    .and_then(|e| e.to_str()).map(String::from),
-                cmd: p.cmd().iter()
-                    .filter_map(|s| s.to_str())
-                    .map(String::from)
-                    .collect(),
-                cwd: p.cwd()
-                    .and_then(|e| e.to_str()).map(String::from)
-            })
-        )
-        .collect()
-}
-
-pub async fn poll() -> Result<(), String> {
-    let processes = get_processes();
-
-    let games = get_games().await?;
+pub static CTX: LazyLock<Mutex<Option<NotificationContext>>> = LazyLock::new(|| Mutex::new(None));
 
-    let open_games: HashSet<String> = processes.into_iter()
-        .map(|p| p.name)
-        .filter(|p| games.games.iter().any(|g| &g.name == p))
-        .collect();
+/// emits `games_changed` with the merged catalogue when the server catalogue changed
+pub async fn refresh_games() -> Result<(), ApiError> {
+    if !api::refresh_games().await? {
+        return Ok(());
+    }
 
-    info!("transmitting activity: {}", if open_games.is_empty() { "(nothing)".to_string() } else { open_games.iter().cloned().collect::<Vec<String>>().join(", ") });
+    let games = get_catalogue().await?;
 
-    let last_put = CTX.lock().await.last_put;
-    let res = put_now_playing(open_games.clone(), last_put).await;
+    info!("games list changed, now {} game(s)", games.len());
+    send_event("games_changed", &games).await.map_err(ApiError::internal)
+}
 
-    match res.as_ref() {
-        Ok(Some(others_playing)) => {
-            info!("received activity from {} player(s)", others_playing.online);
-            send_event("others_playing", others_playing).await?;
-        },
-        Ok(None) => {
-            info!("received not modified");
-        },
-        Err(e) => {
-            warn!("error transmitting activity: {e}");
+pub async fn poll() -> Result<(), ApiError> {
+    let report = processes::report().await?;
 
-            return Err(e.clone());
-        }
+    if let Some(others_playing) = &report.response {
+        send_event("others_playing", others_playing).await
+            .map_err(ApiError::internal)?;
     }
 
-    let mut ctx_lock = CTX.lock().await;
-    let mut others_playing = match (res.unwrap(), ctx_lock.last_response.clone()) {
-        (Some(others_playing), _) => {
-            ctx_lock.last_put = Local::now();
-            ctx_lock.last_response = Some(others_playing.clone());
-
-            others_playing
-        },
-        (None, Some(last)) => last,
-        (None, None) => return Ok(())
-    };
-
-    drop(ctx_lock);
-
-    others_playing.active.sort_by(|a, b| {
-        if a.players.len() != b.players.len() {
-            return b.players.len().cmp(&a.players.len());
-        }
-
-        a.game.label.cmp(&b.game.label)
-    });
+    if report.reported.is_none() {
+        return Ok(());
+    }
 
-    let Some(most_played) = others_playing.active.first() else {
+    let Some(others_playing) = STATE.lock().await.last_response.clone() else {
         return Ok(());
     };
 
     // is majority playing game
-    if most_played.players.len() < others_playing.online / 2 {
+    let Some(most_played) = processes::majority(&others_playing) else {
         return Ok(());
-    }
+    };
 
     // are you playing game
-    if open_games.contains(&most_played.game.name) {
+    if report.detected.iter().any(|g| g.name == most_played.game.name) {
         return Ok(());
     }
 
     let mut ctx_lock = CTX.lock().await;
-    let Some(notification_ctx) = &mut ctx_lock.notification_ctx else {
-        ctx_lock.notification_ctx = Some(NotificationContext::new(&most_played.game));
+    let Some(notification_ctx) = ctx_lock.as_mut() else {
+        *ctx_lock = Some(NotificationContext::new(&most_played.game));
 
         return Ok(());
     };
@@ -189,7 +114,7 @@ pub async fn poll() -> Result<(), String> {
 
     if let Err(e) = Notification::new()
         .summary("LAN Manager")
-        .body(format!("Die Mehrheit spielt {}. Du nicht. Schande!", most_played.game.label).as_str())
+        .body(Message::MajorityPlaying { game: &most_played.game.label }.translate(i18n::language()).as_str())
         .timeout(Timeout::Milliseconds(30_000))
         .image_path(icon.unwrap_or(String::new()).as_str())
         .show() {
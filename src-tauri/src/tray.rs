@@ -0,0 +1,155 @@
+use common::game::Game;
+use log::warn;
+use tauri::{menu::{CheckMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem}, tray::TrayIconBuilder, AppHandle, Manager, Wry};
+
+use lan_core::{config::{self, Privacy}, i18n::{self, Message}, processes::{self, STATE}};
+
+use crate::app::APP_HANDLE;
+
+const TRAY_ID: &str = "main";
+
+struct TrayStatus {
+    detected: Vec<Game>,
+    online: Option<usize>,
+    majority: Option<Game>,
+    privacy: Privacy
+}
+
+impl TrayStatus {
+    async fn current() -> TrayStatus {
+        let state_lock = STATE.lock().await;
+
+        TrayStatus {
+            detected: state_lock.detected.clone(),
+            online: state_lock.last_response.as_ref().map(|r| r.online),
+            majority: state_lock.last_response.as_ref()
+                .and_then(processes::majority)
+                .map(|m| m.game),
+            privacy: config::get_or_create_config(false)
+                .map(|c| c.privacy)
+                .unwrap_or_default()
+        }
+    }
+
+    fn lines(&self) -> Vec<String> {
+        let language = i18n::language();
+        let mut lines = vec![];
+
+        if self.detected.is_empty() {
+            lines.push(Message::TrayNothingDetected.translate(language));
+        } else {
+            let games = self.detected.iter()
+                .map(|g| g.label.as_str())
+                .collect::<Vec<&str>>()
+                .join(", ");
+
+            lines.push(Message::TrayPlaying { games: &games }.translate(language));
+        }
+
+        if let Some(count) = self.online {
+            lines.push(Message::TrayOnline { count }.translate(language));
+        }
+
+        if let Some(game) = &self.majority {
+            lines.push(Message::TrayMajority { game: &game.label }.translate(language));
+        }
+
+        lines
+    }
+}
+
+fn build_menu(app: &AppHandle, status: &TrayStatus) -> tauri::Result<Menu<Wry>> {
+    let language = i18n::language();
+    let menu = Menu::new(app)?;
+
+    for (i, line) in status.lines().into_iter().enumerate() {
+        menu.append(&MenuItem::with_id(app, format!("status_{i}"), line, false, None::<&str>)?)?;
+    }
+
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    menu.append(&CheckMenuItem::with_id(app, "pause", Message::TrayPause.translate(language), true, status.privacy == Privacy::Paused, None::<&str>)?)?;
+    menu.append(&CheckMenuItem::with_id(app, "private", Message::TrayPrivate.translate(language), true, status.privacy == Privacy::Private, None::<&str>)?)?;
+    menu.append(&MenuItem::with_id(app, "open", Message::TrayOpen.translate(language), true, None::<&str>)?)?;
+    menu.append(&MenuItem::with_id(app, "quit", Message::TrayQuit.translate(language), true, None::<&str>)?)?;
+
+    Ok(menu)
+}
+
+/// switches to `privacy`, or back to public if it is already active
+async fn toggle_privacy(privacy: Privacy) {
+    let current = config::get_or_create_config(false)
+        .map(|c| c.privacy)
+        .unwrap_or_default();
+    let privacy = if current == privacy { Privacy::Public } else { privacy };
+
+    if let Err(e) = processes::set_privacy(privacy).await {
+        warn!("failed to change privacy: {e}");
+    }
+
+    update().await;
+}
+
+fn on_menu_event(app: &AppHandle, event: MenuEvent) {
+    match event.id().as_ref() {
+        "pause" => {
+            tauri::async_runtime::spawn(toggle_privacy(Privacy::Paused));
+        },
+        "private" => {
+            tauri::async_runtime::spawn(toggle_privacy(Privacy::Private));
+        },
+        "open" => {
+            let Some(window) = app.get_webview_window("main") else {
+                warn!("no frontend window found");
+                return;
+            };
+
+            if let Err(e) = window.show().and_then(|_| window.unminimize()).and_then(|_| window.set_focus()) {
+                warn!("failed to open window: {e}");
+            }
+        },
+        "quit" => app.exit(0),
+        _ => {}
+    }
+}
+
+pub fn create(app: &AppHandle) -> tauri::Result<()> {
+    let status = TrayStatus {
+        detected: vec![],
+        online: None,
+        majority: None,
+        privacy: Privacy::Public
+    };
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("LAN Manager")
+        .menu(&build_menu(app, &status)?)
+        .show_menu_on_left_click(true)
+        .on_menu_event(on_menu_event);
+
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+
+    builder.build(app)?;
+
+    Ok(())
+}
+
+/// refreshes tooltip and menu from the last poll
+pub async fn update() {
+    let app_lock = APP_HANDLE.lock().await;
+    let Some(app) = app_lock.as_ref() else {
+        return;
+    };
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let status = TrayStatus::current().await;
+    let tooltip = format!("LAN Manager\n{}", status.lines().join("\n"));
+    let res = build_menu(app, &status)
+        .and_then(|menu| tray.set_menu(Some(menu)))
+        .and_then(|_| tray.set_tooltip(Some(tooltip)));
+
+    if let Err(e) = res {
+        warn!("failed to update tray: {e}");
+    }
+}
\ No newline at end of file
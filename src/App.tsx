@@ -2,8 +2,9 @@ import { Button, Divider, Group, Image, Loader, Stack, Text, Title, Tooltip } fr
 import "./App.css";
 import Settings from "./components/Settings";
 import Overview from "./components/Overview";
+import Leaderboards from "./components/Leaderboards";
 import { useCallback, useEffect, useState } from "react";
-import { Config, Game } from "./interfaces";
+import { Config, ErrorMessage, Game } from "./interfaces";
 import { invoke } from "@tauri-apps/api/core";
 import DataCollectionInfo from "./components/DataCollectionInfo";
 import { listen } from "@tauri-apps/api/event";
@@ -14,7 +15,7 @@ function App() {
     const [games, setGames] = useState<Game[] | null>(null);
     const [configError, setConfigError] = useState<string | null>(null);
     const [gamesError, setGamesError] = useState<string | null>(null);
-    const [pollError, setPollError] = useState<string | null>(null);
+    const [pollError, setPollError] = useState<ErrorMessage | null>(null);
 
     const loadConfig = useCallback(() => {
         setConfigError(null);
@@ -23,16 +24,21 @@ function App() {
 
     const loadGames = useCallback(() => {
         setGamesError(null);
-        invoke<Game[]>("get_games").then(setGames).catch(setGamesError);
+        invoke<Game[]>("get_games").then(setGames).catch((e: ErrorMessage) => setGamesError(e.message));
     }, []);
 
     useEffect(() => {
         loadConfig();
         loadGames();
 
-        listen<string | null>("poll_error", e => {
+        listen<ErrorMessage | null>("poll_error", e => {
             setPollError(e.payload);
         });
+
+        listen<Game[]>("games_changed", e => {
+            setGamesError(null);
+            setGames(e.payload);
+        });
     }, []);
 
     return <Stack gap={0} w="100vw" h="100vh" style={{overflow: "hidden"}}>
@@ -43,9 +49,10 @@ function App() {
                     <Title>LAN Manager</Title>
                 </Group>
                 <Group align="center">
-                    {pollError && <Tooltip label={<pre>{pollError}</pre>}>
-                        <Text c="yellow"><AlertTriangle /></Text>
+                    {pollError && <Tooltip label={<pre>{pollError.message}</pre>}>
+                        <Text c={pollError.error.kind === "server" && ["unauthorized", "unprocessable_entity"].includes(pollError.error.code) ? "red" : "yellow"}><AlertTriangle /></Text>
                     </Tooltip>}
+                    <Leaderboards />
                     {config && <Settings config={config} setConfig={setConfig} />}
                 </Group>
             </Group>
@@ -0,0 +1,62 @@
+import { ActionIcon, Button, Group, Stack, TagsInput, Text, TextInput } from "@mantine/core";
+import { invoke } from "@tauri-apps/api/core";
+import { useState } from "react";
+import { Plus, Search, Trash2 } from "react-feather";
+import { CustomGame } from "../interfaces";
+
+interface CustomGamesProps {
+    games: CustomGame[],
+    disabled: boolean,
+    onChange: (games: CustomGame[]) => void
+}
+
+export default function CustomGames({games, disabled, onChange}: CustomGamesProps) {
+    const [discovering, setDiscovering] = useState(false);
+    const [discoverError, setDiscoverError] = useState<string | null>(null);
+
+    function update(index: number, game: CustomGame) {
+        onChange(games.map((g, i) => i === index ? game : g));
+    }
+
+    function discover() {
+        setDiscovering(true);
+        setDiscoverError(null);
+        invoke<CustomGame[]>("discover_games")
+            .then(found => {
+                const known = new Set(games.flatMap(g => g.processes));
+
+                onChange([...games, ...found.filter(g => !g.processes.some(p => known.has(p)))]);
+            })
+            .catch(e => setDiscoverError(String(e)))
+            .finally(() => setDiscovering(false));
+    }
+
+    return <Stack gap="xs">
+        <Text size="sm" fw={500}>Eigene Spiele</Text>
+        {games.map((game, i) => <Group key={i} align="end" wrap="nowrap">
+            <TextInput
+                flex={1}
+                placeholder="Bezeichnung"
+                disabled={disabled}
+                value={game.label}
+                onChange={e => update(i, {...game, label: e.currentTarget.value})}/>
+            <TagsInput
+                flex={2}
+                placeholder="Prozessnamen, z.B. Game.exe"
+                disabled={disabled}
+                value={game.processes}
+                onChange={processes => update(i, {...game, processes})}/>
+            <ActionIcon variant="subtle" color="red" size="lg" disabled={disabled} onClick={() => onChange(games.filter((_, j) => j !== i))}>
+                <Trash2 />
+            </ActionIcon>
+        </Group>)}
+        <Button variant="light" leftSection={<Plus />} disabled={disabled} onClick={() => onChange([...games, {label: "", processes: []}])}>
+            Spiel hinzufügen
+        </Button>
+        <Button variant="subtle" leftSection={<Search />} disabled={disabled} loading={discovering} onClick={discover}>
+            Installierte Spiele suchen
+        </Button>
+        {discoverError && <Text size="xs" c="red">{discoverError}</Text>}
+        <Text size="xs" c="dimmed">Eigene Spiele werden dem Server als Vorschlag für die Spieleliste übermittelt.</Text>
+    </Stack>
+}
\ No newline at end of file
@@ -0,0 +1,81 @@
+import { ActionIcon, Badge, Button, Group, Loader, Modal, SimpleGrid, Stack, Table, Text, Title } from "@mantine/core";
+import { useDisclosure } from "@mantine/hooks";
+import { invoke } from "@tauri-apps/api/core";
+import { useCallback, useEffect, useState } from "react";
+import { Award } from "react-feather";
+import { Achievement, ErrorMessage, Leaderboard } from "../interfaces";
+
+function formatValue(value: number, unit: Leaderboard["unit"]) {
+    if (unit === "count") {
+        return value.toString();
+    }
+
+    const minutes = Math.round(value / 60);
+
+    return `${Math.floor(minutes / 60)}h ${minutes % 60}m`;
+}
+
+function LeaderboardsView() {
+    const [leaderboards, setLeaderboards] = useState<Leaderboard[] | null>(null);
+    const [achievements, setAchievements] = useState<Achievement[] | null>(null);
+    const [error, setError] = useState<string | null>(null);
+
+    const load = useCallback(() => {
+        setError(null);
+        invoke<Leaderboard[]>("get_leaderboards").then(setLeaderboards).catch((e: ErrorMessage) => setError(e.message));
+        invoke<Achievement[]>("get_achievements").then(setAchievements).catch((e: ErrorMessage) => setError(e.message));
+    }, []);
+
+    useEffect(load, []);
+
+    if (error) {
+        return <Stack>
+            <Text c="red"><pre style={{whiteSpace: "pre-wrap"}}>{error}</pre></Text>
+            <Button onClick={load}>Erneut versuchen</Button>
+        </Stack>
+    }
+
+    if (!leaderboards || !achievements) {
+        return <Group p="md" justify="center"><Loader /></Group>
+    }
+
+    return <Stack>
+        <SimpleGrid cols={2}>
+            {leaderboards.map(l => <Stack key={l.kind === "unknown" ? l.label : l.kind} gap="xs">
+                <Title size="md">{l.label}</Title>
+                {l.entries.length === 0 ?
+                    <Text c="dimmed">(noch keine Daten)</Text>
+                :
+                    <Table>
+                        <Table.Tbody>
+                            {l.entries.slice(0, 5).map((e, i) => <Table.Tr key={e.player.id}>
+                                <Table.Td>{i + 1}.</Table.Td>
+                                <Table.Td>{e.player.name}</Table.Td>
+                                <Table.Td ta="right">{formatValue(e.value, l.unit)}</Table.Td>
+                            </Table.Tr>)}
+                        </Table.Tbody>
+                    </Table>
+                }
+            </Stack>)}
+        </SimpleGrid>
+        <Title size="md">Erfolge</Title>
+        <Group gap="xs">
+            {achievements.map(a => <Badge key={a.id} title={a.description} variant={a.unlocked ? "filled" : "outline"} color={a.unlocked ? "yellow" : "gray"}>
+                {a.label}
+            </Badge>)}
+        </Group>
+    </Stack>
+}
+
+export default function Leaderboards() {
+    const [opened, { open, close }] = useDisclosure(false);
+
+    return <>
+        <ActionIcon onClick={open} color={opened ? "blue" : "gray"} variant="subtle" size="xl">
+            <Award />
+        </ActionIcon>
+        <Modal opened={opened} onClose={close} title="Bestenlisten" size="lg">
+            <LeaderboardsView />
+        </Modal>
+    </>
+}
\ No newline at end of file
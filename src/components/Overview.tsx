@@ -1,4 +1,4 @@
-import { Stack } from "@mantine/core";
+import { Stack, Text } from "@mantine/core";
 import { listen } from "@tauri-apps/api/event";
 import { useEffect, useState } from "react";
 import { Config, OthersPlayingResponse } from "../interfaces";
@@ -25,5 +25,8 @@ export default function Overview({config}: {config: Config}) {
 
     return <Stack justify="center" align="center" flex={1}>
         <GameList games={others_playing?.active || []} config={config} />
+        {!!others_playing?.private?.length && <Text c="dimmed">
+            {others_playing.private.map(p => p.name).sort().join(", ")} {others_playing.private.length > 1 ? "sind" : "ist"} privat online
+        </Text>}
     </Stack>
 }
\ No newline at end of file
@@ -1,9 +1,10 @@
-import { ActionIcon, Blockquote, Button, Checkbox, Modal, PasswordInput, Stack, TextInput } from "@mantine/core";
+import { ActionIcon, Blockquote, Button, Checkbox, Modal, MultiSelect, PasswordInput, Select, Stack, TextInput } from "@mantine/core";
 import { useDisclosure } from "@mantine/hooks";
 import { invoke } from "@tauri-apps/api/core";
-import { ChangeEvent, useState } from "react";
+import { ChangeEvent, useEffect, useState } from "react";
 import { AlertCircle, Settings as SettingsIcon } from "react-feather";
-import { Config } from "../interfaces";
+import { Config, Game } from "../interfaces";
+import CustomGames from "./CustomGames";
 
 interface SettingsFormProps {
     config: Config,
@@ -13,6 +14,11 @@ interface SettingsFormProps {
 function SettingsForm({config, setConfig}: SettingsFormProps) {
     const [loading, setLoading] = useState(false);
     const [error, setError] = useState<string | null>(null);
+    const [games, setGames] = useState<Game[]>([]);
+
+    useEffect(() => {
+        invoke<Game[]>("get_games").then(setGames).catch(() => setGames([]));
+    }, []);
 
     function change<T = string>(k: keyof(Config), t?: (e: ChangeEvent<HTMLInputElement>) => T) {
         return (e: ChangeEvent<HTMLInputElement>) => 
@@ -36,6 +42,7 @@ function SettingsForm({config, setConfig}: SettingsFormProps) {
                     <TextInput
                         label="Name"
                         disabled={loading}
+                        maxLength={32}
                         value={config.name || ""}
                         onChange={change("name")}/>
                     <TextInput
@@ -48,6 +55,41 @@ function SettingsForm({config, setConfig}: SettingsFormProps) {
                         disabled={loading}
                         value={config.password || ""}
                         onChange={change("password")}/>
+                    <Select
+                        label="Sprache"
+                        disabled={loading}
+                        data={[{value: "de", label: "Deutsch"}, {value: "en", label: "English"}]}
+                        value={config.language}
+                        allowDeselect={false}
+                        onChange={v => v && setConfig({...config, language: v as Config["language"]})}/>
+                    <Select
+                        label="Privatsphäre"
+                        disabled={loading}
+                        data={[
+                            {value: "public", label: "Spiele übertragen"},
+                            {value: "private", label: "Privat (nur online)"},
+                            {value: "paused", label: "Übertragung pausiert"}
+                        ]}
+                        value={config.privacy}
+                        allowDeselect={false}
+                        onChange={v => v && setConfig({...config, privacy: v as Config["privacy"]})}/>
+                    <MultiSelect
+                        label="Nie übertragene Spiele"
+                        disabled={loading}
+                        searchable
+                        data={games.map(g => ({value: g.name, label: g.label}))}
+                        value={config.hidden_games}
+                        onChange={v => setConfig({...config, hidden_games: v})}/>
+                    <CustomGames
+                        games={config.custom_games}
+                        disabled={loading}
+                        onChange={custom_games => setConfig({...config, custom_games})}/>
+                    <Checkbox
+                        label="Unbekannte Spiele melden"
+                        description="Überträgt Prozesse, die nach Spielen aussehen, als Vorschlag für die Spieleliste."
+                        disabled={loading}
+                        checked={config.report_unknown_processes}
+                        onChange={change("report_unknown_processes", e => e.currentTarget.checked)}/>
                     <Checkbox
                         label="Autostart"
                         disabled={loading}
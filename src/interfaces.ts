@@ -15,7 +15,8 @@ export interface OthersPlayingEntry {
 
 export interface OthersPlayingResponse {
     active: OthersPlayingEntry[],
-    online: number
+    online: number,
+    private: Player[]
 }
 
 export interface Config {
@@ -24,4 +25,45 @@ export interface Config {
     name?: string;
     autostart: boolean;
     password?: string;
+    language: "de" | "en";
+    privacy: "public" | "private" | "paused";
+    hidden_games: string[];
+    custom_games: CustomGame[];
+    report_unknown_processes: boolean;
+}
+
+export interface CustomGame {
+    label: string,
+    processes: string[]
+}
+
+export interface LeaderboardEntry {
+    player: Player,
+    value: number
+}
+
+export interface Leaderboard {
+    kind: "most_hours" | "most_games" | "longest_session" | "night_owl" | "unknown",
+    label: string,
+    unit: "seconds" | "count",
+    entries: LeaderboardEntry[]
+}
+
+export interface Achievement {
+    id: string,
+    label: string,
+    description: string,
+    unlocked: boolean
+}
+
+export type ErrorCode = "bad_request" | "unauthorized" | "forbidden" | "not_found" | "upgrade_required" | "payload_too_large" | "unprocessable_entity" | "too_many_requests" | "internal" | "unknown";
+
+export type ApiError =
+    { kind: "config" | "connection" | "invalid_response" | "internal", message: string } |
+    { kind: "incompatible", server_version: string, min_protocol: number } |
+    { kind: "server", status: number, code: ErrorCode, message: string, details?: string };
+
+export interface ErrorMessage {
+    error: ApiError,
+    message: string
 }
\ No newline at end of file